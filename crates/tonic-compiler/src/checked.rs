@@ -0,0 +1,153 @@
+use tonic_parser::Type;
+use tonic_js_builder::{Builder, Function, IfElse, Var, Expression as JsExpression};
+use std::collections::HashMap;
+
+/// The name of the runtime helper that checked mode calls to validate values.
+const HELPER: &str = "__tonic_expect";
+
+/// Aliases can refer to themselves, so they're only expanded this many times before a value is assumed to be valid.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// Wrap a value in a call to the runtime helper, which throws a `TypeError` if the value doesn't match the type and
+/// returns it untouched otherwise.
+pub(crate) fn guard(r#type: &Type, value: JsExpression, description: String, line: usize, aliases: &HashMap<String, Type>) -> JsExpression {
+    let mut valid = Builder::new();
+    valid.return_(Some(predicate(r#type, JsExpression::identifier("value"), 0, aliases)));
+
+    JsExpression::from((JsExpression::identifier(HELPER), vec![
+        JsExpression::closure(vec![JsExpression::identifier("value")], valid),
+        value,
+        description.into(),
+        r#type.to_string().into(),
+        (line as i64).into(),
+    ]))
+}
+
+/// The runtime helper used by `guard()`:
+///
+/// ```js
+/// function __tonic_expect(valid, value, name, expected, line) {
+///     if (!valid(value)) {
+///         let found = typeof value;
+///         ...
+///         throw TypeError(name + " expected `" + expected + "` but found " + found + " on line " + line);
+///     }
+///     return value;
+/// }
+/// ```
+pub(crate) fn helper() -> Function {
+    let id = JsExpression::identifier;
+    let concat = |parts: Vec<JsExpression>| parts.into_iter().reduce(|left, right| JsExpression::infix(left, "+", right)).unwrap();
+
+    let mut found = Var::new();
+    found.id("found".to_owned()).as_let().value(JsExpression::Prefix("typeof".to_owned(), Box::new(id("value"))));
+
+    let rename = |condition: JsExpression, name: &str| {
+        let mut then = Builder::new();
+        then.expression(JsExpression::infix(id("found"), "=", name.into()));
+
+        let mut if_ = IfElse::new(condition);
+        if_.then(then);
+        if_
+    };
+
+    let mut invalid = Builder::new();
+    invalid
+        .var(found)
+        .conditional(rename(JsExpression::infix(id("value"), "===", JsExpression::null()), "null"))
+        .conditional(rename(is_array(id("value")), "array"))
+        .throw(JsExpression::from((id("TypeError"), vec![concat(vec![
+            id("name"),
+            " expected `".into(),
+            id("expected"),
+            "` but found ".into(),
+            id("found"),
+            " on line ".into(),
+            id("line"),
+        ])])));
+
+    let mut check = IfElse::new(JsExpression::Prefix("!".to_owned(), Box::new(JsExpression::from((id("valid"), vec![id("value")])))));
+    check.then(invalid);
+
+    let mut body = Builder::new();
+    body.conditional(check).return_(Some(id("value")));
+
+    let mut function = Function::new();
+    function
+        .id(HELPER.to_owned())
+        .parameters(["valid", "value", "name", "expected", "line"].into_iter().map(id).collect())
+        .body(body);
+
+    function
+}
+
+/// Build a JavaScript expression that checks whether `subject` matches a type.
+fn predicate(r#type: &Type, subject: JsExpression, depth: usize, aliases: &HashMap<String, Type>) -> JsExpression {
+    let all = |items: JsExpression, item: &Type| {
+        let parameter = JsExpression::identifier(format!("value{}", depth + 1));
+
+        let mut valid = Builder::new();
+        valid.return_(Some(predicate(item, parameter.clone(), depth + 1, aliases)));
+
+        JsExpression::from((
+            JsExpression::dot(items, JsExpression::identifier("every")),
+            vec![JsExpression::closure(vec![parameter], valid)],
+        ))
+    };
+
+    match r#type {
+        Type::String => type_of(subject, "string"),
+        Type::Number => type_of(subject, "number"),
+        Type::BigInt => type_of(subject, "bigint"),
+        Type::Bool => type_of(subject, "boolean"),
+        Type::Function(..) => type_of(subject, "function"),
+        Type::Array(item) => JsExpression::infix(is_array(subject.clone()), "&&", all(subject, item)),
+        Type::Map(_, value) => {
+            let values = JsExpression::from((
+                JsExpression::dot(JsExpression::identifier("Object"), JsExpression::identifier("values")),
+                vec![subject.clone()],
+            ));
+
+            JsExpression::infix(is_object(subject), "&&", all(values, value))
+        },
+        Type::Record(fields) => fields.iter().fold(is_object(subject.clone()), |valid, (key, r#type)| {
+            JsExpression::infix(valid, "&&", predicate(r#type, JsExpression::index(subject.clone(), key.as_str().into()), depth + 1, aliases))
+        }),
+        Type::Optional(inner) => JsExpression::infix(
+            JsExpression::infix(
+                JsExpression::infix(subject.clone(), "===", JsExpression::null()),
+                "||",
+                JsExpression::infix(subject.clone(), "===", JsExpression::identifier("undefined")),
+            ),
+            "||",
+            predicate(inner, subject, depth, aliases),
+        ),
+        Type::Union(types) => types.iter()
+            .map(|t| predicate(t, subject.clone(), depth, aliases))
+            .reduce(|left, right| JsExpression::infix(left, "||", right))
+            .unwrap_or_else(|| false.into()),
+        Type::Named(name) => match aliases.get(name) {
+            Some(alias) if depth < MAX_ALIAS_DEPTH => predicate(alias, subject, depth + 1, aliases),
+            _ => true.into(),
+        },
+    }
+}
+
+fn type_of(subject: JsExpression, name: &str) -> JsExpression {
+    JsExpression::infix(JsExpression::Prefix("typeof".to_owned(), Box::new(subject)), "===", name.into())
+}
+
+fn is_array(subject: JsExpression) -> JsExpression {
+    JsExpression::from((
+        JsExpression::dot(JsExpression::identifier("Array"), JsExpression::identifier("isArray")),
+        vec![subject],
+    ))
+}
+
+fn is_object(subject: JsExpression) -> JsExpression {
+    JsExpression::infix(
+        JsExpression::infix(type_of(subject.clone(), "object"), "&&", JsExpression::infix(subject.clone(), "!==", JsExpression::null())),
+        "&&",
+        JsExpression::Prefix("!".to_owned(), Box::new(is_array(subject))),
+    )
+}
\ No newline at end of file
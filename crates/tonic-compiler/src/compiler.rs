@@ -1,109 +1,238 @@
-use tonic_parser::{Statement, Expression, Op};
-use tonic_js_builder::{Builder, Var, While, IfElse, Function, Expression as JsExpression};
+use tonic_parser::{Statement, Node, Expression, Op, Parameter, Pattern, Type, Arm, Position};
+use tonic_js_builder::{Builder, Var, While, For, IfElse, Function, Expression as JsExpression, Statement as JsStatement};
+use crate::{Diagnostic, checked, range, entries, pattern};
+use std::collections::{BTreeSet, HashMap};
 use std::vec::IntoIter;
 
+type CompilerResult<T> = Result<T, Diagnostic>;
+
+/// Functions that the compiled code relies on at runtime. Each one is only added to the output if it's used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Helper {
+    Expect,
+    Range,
+    Entries,
+}
+
 #[derive(Debug)]
 pub(crate) struct Compiler {
-    ast: IntoIter<Statement>,
+    ast: IntoIter<Node>,
     builder: Builder,
+    diagnostics: Vec<Diagnostic>,
+    /// Whether to guard annotated parameters and return values with runtime type assertions.
+    checked: bool,
+    /// The runtime helpers that the compiled code calls, which are added to the end of the program.
+    helpers: BTreeSet<Helper>,
+    aliases: HashMap<String, Type>,
+    /// The name and return type of the function being compiled, if its return value needs checking.
+    function: Option<(String, Type)>,
+    /// How many `match` expressions have been compiled, so that each one can name its subject uniquely.
+    matches: usize,
+    line: usize,
+}
+
+/// What an arm of a `match` does once its body has run.
+enum Exit<'a> {
+    /// Carry on to whatever comes after the arm, because it's the last one.
+    Fallthrough,
+    /// Leave the labelled block that holds the arms.
+    Break(&'a str),
+    /// Return from the function that holds the arms, since the `match` produces a value.
+    Return,
 }
 
 impl Compiler {
-    pub fn new(ast: IntoIter<Statement>) -> Self {
+    pub fn new(ast: IntoIter<Node>) -> Self {
         Self {
             ast,
             builder: Builder::new(),
+            diagnostics: Vec::new(),
+            checked: false,
+            helpers: BTreeSet::new(),
+            aliases: HashMap::new(),
+            function: None,
+            matches: 0,
+            line: 0,
         }
     }
 
-    fn compile_statement(&mut self, statement: Statement) {
+    pub fn checked(&mut self, checked: bool) -> &mut Self {
+        self.checked = checked;
+        self
+    }
+
+    fn compile_statement(&mut self, statement: Statement) -> CompilerResult<()> {
         match statement {
             Statement::Use { module, imports } => {
                 self.builder.import(imports, module);
             },
-            Statement::Let { identifier, initial, .. } => {
+            // There's nothing to declare, but the value still needs evaluating for its side effects.
+            Statement::Let { pattern: Pattern::Wildcard, initial, .. } => {
+                let expression = self.compile_expression(initial)?;
+
+                self.builder.expression(expression);
+            },
+            Statement::Let { pattern, initial, constant, .. } => {
                 let mut var = Var::new();
                 
-                var.id(identifier)
+                var.pattern(destructure(&pattern)?)
                     .as_let()
-                    .value(self.compile_expression(initial));
+                    .value(self.compile_expression(initial)?);
+
+                if constant {
+                    var.as_const();
+                }
 
                 self.builder.var(var);
             },
-            Statement::Function { identifier, parameters, body, .. } => {
+            Statement::Function { identifier, parameters, body, return_type } => {
+                let context = if self.checked { return_type.map(|t| (identifier.clone(), t)) } else { None };
+                let previous = std::mem::replace(&mut self.function, context);
+
                 let mut function = Function::new();
-                
-                let mut body = Compiler::new(body.into_iter());
-                body.compile();
+                let parameters = self.parameters(parameters, &identifier);
+                let body = self.block(body);
+
+                self.function = previous;
+                let (parameters, mut guarded) = parameters?;
+                guarded.append(body);
 
                 function
                     .id(identifier)
-                    .parameters(
-                        parameters.into_iter().map(|p| JsExpression::identifier(p.name)).collect::<Vec<JsExpression>>()
-                    )
-                    .body(body.builder());
+                    .parameters(parameters)
+                    .body(guarded);
 
                 self.builder.function(function);
             },
             Statement::Return { expression } => {
-                let expression = self.compile_expression(expression);
+                let mut expression = self.compile_expression(expression)?;
+
+                if let Some((name, r#type)) = &self.function {
+                    expression = checked::guard(r#type, expression, format!("return value of `{}`", name), self.line, &self.aliases);
+                    self.helpers.insert(Helper::Expect);
+                }
+
                 self.builder.return_(Some(expression));
             },
-            Statement::While { condition, then } => {
-                let condition = self.compile_expression(condition);
-                let mut then = Compiler::new(then.into_iter());
-                then.compile();
+            Statement::While { label, condition, then } => {
+                let condition = self.compile_expression(condition)?;
+                let then = self.block(then);
 
                 let mut while_ = While::new(condition);
-                while_.then(then.builder());
+                while_.then(then);
+
+                if let Some(label) = label {
+                    while_.label(label);
+                }
 
                 self.builder.while_loop(while_);
             },
-            Statement::If { condition, then, otherwise } => {
-                let condition = self.compile_expression(condition);
+            Statement::For { label, key, value, iterable, then } => {
+                let mut for_ = match (key, iterable) {
+                    (None, Expression::Range { start, end, inclusive, step }) => match (&value, step.as_deref().map_or(Some(1.0), literal)) {
+                        // When we know which way a range is counting, it's lowered to a counted loop instead of calling the range helper.
+                        (Pattern::Binding(_) | Pattern::Wildcard, Some(step)) if step != 0.0 => {
+                            let JsExpression::Identifier(counter) = destructure(&value)? else { unreachable!() };
+
+                            self.counted(counter, start, end, inclusive, step)?
+                        },
+                        _ => For::of(destructure(&value)?, self.range(start, end, inclusive, step)?),
+                    },
+                    (None, iterable) => For::of(destructure(&value)?, self.compile_expression(iterable)?),
+                    (Some(key), iterable) => {
+                        self.helpers.insert(Helper::Entries);
+
+                        For::of(
+                            JsExpression::Array(vec![JsExpression::identifier(key), destructure(&value)?]),
+                            entries::call(self.compile_expression(iterable)?),
+                        )
+                    },
+                };
+
+                for_.then(self.block(then));
+
+                if let Some(label) = label {
+                    for_.label(label);
+                }
 
-                let mut then = Compiler::new(then.into_iter());
-                then.compile();
+                self.builder.for_loop(for_);
+            },
+            Statement::If { condition, then, otherwise } => {
+                let condition = self.compile_expression(condition)?;
+                let then = self.block(then);
 
                 let mut if_ = IfElse::new(condition);
                 if_
-                    .then(then.builder());
+                    .then(then);
 
                 if ! otherwise.is_empty() {
-                    let mut otherwise = Compiler::new(otherwise.into_iter());
-                    otherwise.compile();
+                    let otherwise = self.block(otherwise);
 
-                    if_.otherwise(otherwise.builder());
+                    if_.otherwise(otherwise);
                 }
 
                 self.builder.conditional(if_);
             },
+            Statement::Expression { expression: Expression::Match(subject, arms, position) } => {
+                self.match_statement(*subject, arms, position)?;
+            },
             Statement::Expression { expression } => {
-                let expression = self.compile_expression(expression);
+                let expression = self.compile_expression(expression)?;
 
                 self.builder.expression(expression);
             },
-            _ => unimplemented!("compile statement {:?}", statement),
-        }
-    }
+            Statement::Break { label } => {
+                self.builder.break_(label);
+            },
+            Statement::Continue { label } => {
+                self.builder.continue_(label);
+            },
+            // Types only exist at compile time, so aliases don't produce any code.
+            Statement::TypeAlias { .. } => {},
+        };
 
-    fn compile_expression(&mut self, expression: Expression) -> JsExpression {
-        use std::collections::HashMap;
+        Ok(())
+    }
 
-        match expression {
+    fn compile_expression(&mut self, expression: Expression) -> CompilerResult<JsExpression> {
+        Ok(match expression {
             Expression::String(s) => s.into(),
+            Expression::Interpolated(parts) => {
+                let mut strings = vec![String::new()];
+                let mut expressions = Vec::new();
+
+                for part in parts {
+                    match part {
+                        Expression::String(s) => strings.last_mut().unwrap().push_str(&s),
+                        part => {
+                            expressions.push(self.compile_expression(part)?);
+                            strings.push(String::new());
+                        },
+                    }
+                }
+
+                JsExpression::template(strings, expressions)
+            },
             Expression::Number(n) => n.into(),
+            Expression::BigInt(n) => JsExpression::bigint(n.as_str()),
             Expression::Bool(b) => b.into(),
-            Expression::Array(items) => items.into_iter().map(|i| self.compile_expression(i)).collect::<Vec<JsExpression>>().into(),
+            Expression::Null => JsExpression::null(),
+            Expression::Array(items) => items.into_iter().map(|i| self.compile_expression(i)).collect::<CompilerResult<Vec<JsExpression>>>()?.into(),
             Expression::Map(members) => {
-                let members = members.into_iter().map(|(k, v)| (k, self.compile_expression(v))).collect::<HashMap<String, JsExpression>>();
+                let members = members.into_iter().map(|(k, v)| Ok((k, self.compile_expression(v)?))).collect::<CompilerResult<Vec<(String, JsExpression)>>>()?;
 
                 JsExpression::Object(members)
             },
             Expression::Identifier(i) => JsExpression::identifier(i),
-            Expression::Infix(left, op, right) => {
+            Expression::Infix(left, op, _, position) if op.assigns() && optional(&left) => {
+                return Err(Diagnostic::at("cannot assign to an optional chain", position))
+            },
+            Expression::Infix(left, op, right, position) => {
+                // `null` stands in for both of JavaScript's missing values, so comparing against it also catches `undefined`.
+                let null = *left == Expression::Null || *right == Expression::Null;
+
                 JsExpression::from((
-                    self.compile_expression(*left),
+                    self.compile_expression(*left)?,
                     (match op {
                         Op::GreaterThan => ">",
                         Op::LessThan => "<",
@@ -113,37 +242,54 @@ impl Compiler {
                         Op::Subtract => "-",
                         Op::Multiply => "*",
                         Op::Divide => "/",
+                        Op::Equals if null => "==",
+                        Op::NotEquals if null => "!=",
                         Op::Equals => "===",
                         Op::NotEquals => "!==",
                         Op::And => "&&",
                         Op::Or => "||",
                         Op::Mod => "%",
+                        Op::Power => "**",
                         Op::AddAssign => "+=",
                         Op::SubtractAssign => "-=",
                         Op::MultiplyAssign => "*=",
                         Op::DivideAssign => "/=",
-                        _ => unimplemented!(),
+                        Op::PowerAssign => "**=",
+                        Op::Coalesce => "??",
+                        Op::CoalesceAssign => "??=",
+                        _ => return Err(Diagnostic::at(format!("unsupported infix operator `{:?}`", op), position)),
                     }).to_string(),
-                    self.compile_expression(*right),
+                    self.compile_expression(*right)?,
                 ))
             },
             Expression::Call(callable, args) => {
                 JsExpression::Call(
-                    Box::new(self.compile_expression(*callable)),
-                    args.into_iter().map(|a| self.compile_expression(a)).collect::<Vec<JsExpression>>()
+                    Box::new(self.compile_expression(*callable)?),
+                    args.into_iter().map(|a| self.compile_expression(a)).collect::<CompilerResult<Vec<JsExpression>>>()?
                 )
             },
-            Expression::Assign(target, value) => {
+            Expression::Assign(target, _, position) if matches!(target.as_ref(), Expression::Index(_, Some(index)) if matches!(index.as_ref(), Expression::Range { .. })) => {
+                return Err(Diagnostic::at("cannot assign to a slice", position))
+            },
+            Expression::Assign(target, _, position) if optional(&target) => {
+                return Err(Diagnostic::at("cannot assign to an optional chain", position))
+            },
+            Expression::Assign(target, value, _) => {
                 // TODO: Add support for more convenient assignment operators - `+=`, `-=`, `*=`, etc.
-                JsExpression::infix(self.compile_expression(*target), "=", self.compile_expression(*value))
+                JsExpression::infix(self.compile_expression(*target)?, "=", self.compile_expression(*value)?)
+            },
+            Expression::Index(target, Some(index)) if matches!(index.as_ref(), Expression::Range { .. }) => {
+                let Expression::Range { start, end, inclusive, step } = *index else { unreachable!() };
+
+                self.slice(*target, start, end, inclusive, step)?
             },
             Expression::Index(array, index) => {
                 // If we're appending a value, i.e. `items[] = ...`, we don't want to use the normal syntax and instead
                 // want to meta-program a `.length` index so that the value is added to the end of the array.
                 if let Some(index) = index {
-                    JsExpression::index(self.compile_expression(*array), self.compile_expression(*index))
+                    JsExpression::index(self.compile_expression(*array)?, self.compile_expression(*index)?)
                 } else {
-                    let array = self.compile_expression(*array);
+                    let array = self.compile_expression(*array)?;
 
                     JsExpression::index(
                         array.clone(),
@@ -156,39 +302,586 @@ impl Compiler {
             },
             Expression::Dot(object, property) => {
                 JsExpression::dot(
-                    self.compile_expression(*object),
-                    self.compile_expression(*property)
+                    self.compile_expression(*object)?,
+                    self.compile_expression(*property)?
                 )
             },
+            Expression::OptionalIndex(target, index) => {
+                JsExpression::optional_index(self.compile_expression(*target)?, self.compile_expression(*index)?)
+            },
+            Expression::OptionalDot(object, property) => {
+                JsExpression::optional_dot(self.compile_expression(*object)?, self.compile_expression(*property)?)
+            },
             Expression::Closure(parameters, body) => {
-                let mut body = Compiler::new(body.into_iter());
-                body.compile();
+                // Closures don't have a return type, so any return statements inside of them are left alone.
+                let previous = self.function.take();
 
-                JsExpression::closure(
-                    parameters.into_iter().map(|p| JsExpression::identifier(p.name)).collect::<Vec<JsExpression>>(),
-                    body.builder()
-                )
+                let parameters = self.parameters(parameters, "closure");
+                let body = self.block(body);
+
+                self.function = previous;
+                let (parameters, mut guarded) = parameters?;
+                guarded.append(body);
+
+                JsExpression::closure(parameters, guarded)
             },
+            Expression::Range { start, end, inclusive, step } => self.range(start, end, inclusive, step)?,
+            Expression::Match(subject, arms, position) => self.match_expression(*subject, arms, position)?,
+            Expression::If { condition, then, otherwise, position } => self.if_expression(*condition, then, otherwise, position)?,
             Expression::Prefix(op, value) => {
                 JsExpression::Prefix(match op {
                     Op::Not => "!".to_owned(),
                     Op::Subtract => "-".to_owned(),
-                    _ => unreachable!()
-                }, Box::new(self.compile_expression(*value)))
+                    _ => return Err(Diagnostic::unpositioned(format!("unsupported prefix operator `{:?}`", op))),
+                }, Box::new(self.compile_expression(*value)?))
             },
-            _ => unimplemented!("compile expression {:?}", expression),
+        })
+    }
+
+    /// Lower a range with a known step to a counted loop, such as `for (let i = 0; i < 10; i += 1)`.
+    fn counted(&mut self, value: String, start: Option<Box<Expression>>, end: Option<Box<Expression>>, inclusive: bool, step: f64) -> CompilerResult<For> {
+        let counter = JsExpression::identifier(value.clone());
+
+        let start = match start {
+            Some(start) => self.compile_expression(*start)?,
+            None => 0.into(),
+        };
+
+        let mut declarations = vec![(value.clone(), start)];
+
+        let condition = match end {
+            // A range without an end carries on until the loop breaks.
+            None => true.into(),
+            Some(end) => {
+                // The end of the range is only evaluated once, before the first iteration.
+                let end = match self.compile_expression(*end)? {
+                    end @ JsExpression::Number(_) => end,
+                    end => {
+                        let id = format!("{}$end", value);
+                        declarations.push((id.clone(), end));
+
+                        JsExpression::identifier(id)
+                    },
+                };
+
+                let comparison = match (step > 0.0, inclusive) {
+                    (true, false) => "<",
+                    (true, true) => "<=",
+                    (false, false) => ">",
+                    (false, true) => ">=",
+                };
+
+                JsExpression::infix(counter.clone(), comparison, end)
+            },
+        };
+
+        let update = if step > 0.0 {
+            JsExpression::infix(counter, "+=", step.into())
+        } else {
+            JsExpression::infix(counter, "-=", (-step).into())
+        };
+
+        Ok(For::counted(declarations, condition, update))
+    }
+
+    /// Compile a range into a call to the range helper, which can be looped over like any other iterable.
+    fn range(&mut self, start: Option<Box<Expression>>, end: Option<Box<Expression>>, inclusive: bool, step: Option<Box<Expression>>) -> CompilerResult<JsExpression> {
+        let start = start.map(|start| self.compile_expression(*start)).transpose()?.unwrap_or_else(|| 0.into());
+        let end = end.map(|end| self.compile_expression(*end)).transpose()?.unwrap_or_else(|| JsExpression::identifier("Infinity"));
+        let step = step.map(|step| self.compile_expression(*step)).transpose()?.unwrap_or_else(|| 1.into());
+
+        self.helpers.insert(Helper::Range);
+
+        Ok(range::call(start, end, step, inclusive))
+    }
+
+    /// Compile `target[start..end]` into a call to `slice()`, which works the same way on both arrays and strings.
+    fn slice(&mut self, target: Expression, start: Option<Box<Expression>>, end: Option<Box<Expression>>, inclusive: bool, step: Option<Box<Expression>>) -> CompilerResult<JsExpression> {
+        if step.is_some() {
+            return Err(Diagnostic::unpositioned("a range with a `step` can't be used to slice"));
         }
+
+        let target = self.compile_expression(target)?;
+
+        let mut arguments = vec![match start {
+            Some(start) => self.compile_expression(*start)?,
+            None => 0.into(),
+        }];
+
+        // `..=-1` runs up to the last item, and slicing up to `-1 + 1` would give nothing, so it's left open instead. An
+        // end that's only known when the program runs does the same by slicing up to `undefined` when it's `-1`.
+        if let Some(end) = end {
+            match literal(&end) {
+                Some(-1.0) if inclusive => {},
+                Some(n) if inclusive => arguments.push((n + 1.0).into()),
+                _ if inclusive => arguments.push(JsExpression::infix(
+                    JsExpression::infix(self.compile_expression(*end)?, "+", 1.into()),
+                    "||",
+                    JsExpression::identifier("undefined"),
+                )),
+                _ => arguments.push(self.compile_expression(*end)?),
+            }
+        }
+
+        Ok(JsExpression::from((JsExpression::dot(target, JsExpression::identifier("slice")), arguments)))
     }
 
-    pub fn compile(&mut self) -> String {
-        while let Some(statement) = self.ast.next() {
-            self.compile_statement(statement);
+    /// Compile a `match` that's used as a statement. Without any guards, the arms become an `if` / `else if` chain.
+    /// Otherwise, an arm whose guard fails has to fall through to the next one, so the arms are kept in a labelled block
+    /// that each arm breaks out of once its body has run.
+    fn match_statement(&mut self, subject: Expression, arms: Vec<Arm>, position: Position) -> CompilerResult<()> {
+        validate(&arms, position)?;
+
+        let name = self.fresh_match();
+        let guarded = arms.iter().any(|arm| arm.guard.is_some());
+        let mut statements = Builder::new();
+
+        // The subject is only evaluated once, so anything other than a variable is kept in a temporary.
+        // An arm that binds the subject's own name would shadow it before it's read, so it needs a temporary too.
+        let shadowed = |name: &str| arms.iter().any(|arm| arm.pattern.bindings().contains(&name));
+
+        let subject = match self.compile_expression(subject)? {
+            JsExpression::Identifier(subject) if ! shadowed(&subject) => JsExpression::identifier(subject),
+            subject => {
+                let mut var = Var::new();
+                var.id(name.clone()).as_let().value(subject);
+                statements.var(var);
+
+                JsExpression::identifier(name.clone())
+            },
+        };
+
+        let mut compiled = Vec::new();
+
+        for arm in arms {
+            let last = arm.guard.is_none() && arm.pattern.irrefutable();
+            let exit = if guarded && ! last { Exit::Break(&name) } else { Exit::Fallthrough };
+
+            compiled.push(self.arm(arm, &subject, exit)?);
+
+            // Anything after an arm that matches everything can never run.
+            if last {
+                break;
+            }
+        }
+
+        if guarded {
+            let mut block = Builder::new();
+
+            for (condition, then) in compiled {
+                match condition {
+                    Some(condition) => {
+                        let mut if_ = IfElse::new(condition);
+                        if_.then(then);
+                        block.conditional(if_);
+                    },
+                    None => {
+                        unconditional(&mut block, then);
+                    },
+                }
+            }
+
+            statements.block(Some(name), block);
+        } else if let [(None, _)] = compiled.as_slice() {
+            // The first arm matches everything, so there's no `if` to scope its bindings and they need a block of
+            // their own.
+            let (_, then) = compiled.remove(0);
+
+            statements.block(None, then);
+        } else {
+            let chain = compiled.into_iter().rev().fold(None, |otherwise: Option<Builder>, (condition, then)| match condition {
+                Some(condition) => {
+                    let mut if_ = IfElse::new(condition);
+                    if_.then(then);
+
+                    if let Some(otherwise) = otherwise {
+                        if_.otherwise(otherwise);
+                    }
+
+                    let mut chain = Builder::new();
+                    chain.conditional(if_);
+
+                    Some(chain)
+                },
+                None => Some(then),
+            });
+
+            statements.append(chain.unwrap_or_default());
+        }
+
+        for statement in statements.statements() {
+            self.builder.statement(statement.clone());
         }
 
-        self.builder.source()
+        Ok(())
+    }
+
+    /// Compile a `match` that produces a value into an arrow function that's called with the subject, where each arm
+    /// returns the value of its last expression.
+    fn match_expression(&mut self, subject: Expression, arms: Vec<Arm>, position: Position) -> CompilerResult<JsExpression> {
+        validate(&arms, position)?;
+
+        if arms.iter().any(|arm| escapes(&arm.body, &mut Vec::new())) {
+            return Err(Diagnostic::at("`return`, `break` and `continue` can't be used inside of a `match` that produces a value", position));
+        }
+
+        if ! arms.iter().all(|arm| produces(&arm.body)) {
+            return Err(Diagnostic::at("`match` arm doesn't produce a value, end it with an expression", position));
+        }
+
+        let subject = self.compile_expression(subject)?;
+        let parameter = JsExpression::identifier(self.fresh_match());
+
+        // Any `return` inside of the arms belongs to the arrow function, so it shouldn't be checked against the
+        // enclosing function's return type.
+        let function = self.function.take();
+        let body = self.value_arms(arms, &parameter);
+        self.function = function;
+
+        Ok(JsExpression::from((JsExpression::closure(vec![parameter], body?), vec![subject])))
+    }
+
+    fn value_arms(&mut self, arms: Vec<Arm>, subject: &JsExpression) -> CompilerResult<Builder> {
+        let mut body = Builder::new();
+
+        for mut arm in arms {
+            let last = arm.guard.is_none() && arm.pattern.irrefutable();
+
+            arm.body = returning(arm.body);
+
+            match self.arm(arm, subject, Exit::Return)? {
+                (Some(condition), then) => {
+                    let mut if_ = IfElse::new(condition);
+                    if_.then(then);
+                    body.conditional(if_);
+                },
+                (None, then) => {
+                    unconditional(&mut body, then);
+                },
+            }
+
+            if last {
+                break;
+            }
+        }
+
+        Ok(body)
+    }
+
+    /// Compile an `if` that produces a value. When every branch is a single expression, it becomes a conditional
+    /// expression, otherwise the branches run inside of an arrow function that returns their last expression.
+    fn if_expression(&mut self, condition: Expression, then: Vec<Node>, otherwise: Vec<Node>, position: Position) -> CompilerResult<JsExpression> {
+        if ! complete(&otherwise) {
+            return Err(Diagnostic::at("`if` is missing an `else` branch, add one so that it produces a value either way", position));
+        }
+
+        if escapes(&then, &mut Vec::new()) || escapes(&otherwise, &mut Vec::new()) {
+            return Err(Diagnostic::at("`return`, `break` and `continue` can't be used inside of an `if` that produces a value", position));
+        }
+
+        if ! (produces(&then) && produces(&otherwise)) {
+            return Err(Diagnostic::at("`if` branch doesn't produce a value, end it with an expression", position));
+        }
+
+        let condition = self.compile_expression(condition)?;
+
+        if simple(&then) && simple(&otherwise) {
+            return Ok(JsExpression::conditional(condition, self.branch(then)?, self.branch(otherwise)?))
+        }
+
+        // Any `return` inside of the branches belongs to the arrow function, so it shouldn't be checked against the
+        // enclosing function's return type.
+        let function = self.function.take();
+        let then = self.block(returning(then));
+        let otherwise = self.block(returning(otherwise));
+        self.function = function;
+
+        let mut if_ = IfElse::new(condition);
+        if_.then(then).otherwise(otherwise);
+
+        let mut body = Builder::new();
+        body.conditional(if_);
+
+        Ok(JsExpression::from((JsExpression::closure(Vec::new(), body), Vec::new())))
+    }
+
+    /// Compile a branch of an `if` expression that `simple()` has said is just a value.
+    fn branch(&mut self, mut nodes: Vec<Node>) -> CompilerResult<JsExpression> {
+        match nodes.pop() {
+            Some(Node { statement: Statement::Expression { expression }, .. }) => self.compile_expression(expression),
+            Some(Node { statement: Statement::If { condition, then, otherwise }, line, span }) => {
+                self.if_expression(condition, then, otherwise, Position::new(line, span))
+            },
+            _ => unreachable!("only simple branches are compiled to conditional expressions"),
+        }
+    }
+
+    /// Compile an arm into the condition that its pattern needs, and a block that binds the pattern's names before
+    /// running its guard and body.
+    fn arm(&mut self, arm: Arm, subject: &JsExpression, exit: Exit) -> CompilerResult<(Option<JsExpression>, Builder)> {
+        let condition = pattern::condition(&arm.pattern, subject);
+        let mut then = Builder::new();
+
+        for (name, value) in pattern::bindings(&arm.pattern, subject) {
+            let mut var = Var::new();
+            var.id(name).as_let().value(value);
+            then.var(var);
+        }
+
+        let mut body = self.block(arm.body);
+
+        match exit {
+            Exit::Fallthrough => {},
+            Exit::Break(label) => {
+                body.break_(Some(label.to_owned()));
+            },
+            Exit::Return => {
+                if ! matches!(body.statements().last(), Some(JsStatement::Return(_))) {
+                    body.return_(None);
+                }
+            },
+        }
+
+        match arm.guard {
+            Some(guard) => {
+                let mut if_ = IfElse::new(self.compile_expression(guard)?);
+                if_.then(body);
+                then.conditional(if_);
+            },
+            None => {
+                then.append(body);
+            },
+        }
+
+        Ok((condition, then))
+    }
+
+    fn fresh_match(&mut self) -> String {
+        self.matches += 1;
+
+        format!("match${}", self.matches)
+    }
+
+    /// Compile a function's parameters, along with the statements that need to run before its body. In checked mode,
+    /// these are the runtime type assertions for its annotated parameters.
+    ///
+    /// An annotated parameter that's destructured is checked as a whole, so it's passed in under a temporary name and
+    /// destructured once its type has been asserted.
+    fn parameters(&mut self, parameters: Vec<Parameter>, function: &str) -> CompilerResult<(Vec<JsExpression>, Builder)> {
+        let mut compiled = Vec::new();
+        let mut prologue = Builder::new();
+
+        let mut names = parameters.iter().flat_map(|parameter| parameter.pattern.bindings()).collect::<Vec<&str>>();
+        names.sort_unstable();
+
+        if let Some(name) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(Diagnostic::unpositioned(format!("`{}` is bound by more than one parameter of `{}`", name[0], function)));
+        }
+
+        for (i, Parameter { pattern, r#type }) in parameters.into_iter().enumerate() {
+            // JavaScript doesn't allow two parameters with the same name, so each `_` gets a name of its own.
+            let target = match pattern {
+                Pattern::Wildcard => JsExpression::identifier(format!("_${}", i + 1)),
+                _ => destructure(&pattern)?,
+            };
+
+            let Some(r#type) = r#type.filter(|_| self.checked) else {
+                compiled.push(target);
+                continue;
+            };
+
+            let (name, description) = match pattern {
+                Pattern::Binding(name) => (name.clone(), format!("parameter `{}` of `{}`", name, function)),
+                _ => (format!("arg${}", i + 1), format!("parameter {} of `{}`", i + 1, function)),
+            };
+
+            prologue.expression(checked::guard(&r#type, JsExpression::identifier(name.as_str()), description, self.line, &self.aliases));
+            self.helpers.insert(Helper::Expect);
+
+            if ! matches!(target, JsExpression::Identifier(_)) {
+                let mut var = Var::new();
+                var.pattern(target).as_let().value(JsExpression::identifier(name.as_str()));
+                prologue.var(var);
+            }
+
+            compiled.push(JsExpression::identifier(name));
+        }
+
+        Ok((compiled, prologue))
+    }
+
+    /// Compile a nested block of statements with a fresh `Compiler`, keeping hold of any diagnostics
+    /// it produces so that they're reported alongside our own.
+    fn block(&mut self, nodes: Vec<Node>) -> Builder {
+        let mut block = Compiler::new(nodes.into_iter());
+        block.checked = self.checked;
+        block.aliases = self.aliases.clone();
+        block.function = self.function.clone();
+        block.matches = self.matches;
+        block.compile_statements();
+
+        self.matches = block.matches;
+        self.diagnostics.append(&mut block.diagnostics);
+        self.helpers.extend(block.helpers.iter().copied());
+
+        block.builder()
+    }
+
+    fn compile_statements(&mut self) {
+        // Aliases can be used before they're defined, so collect them up-front for the runtime type assertions.
+        for node in self.ast.as_slice() {
+            if let Statement::TypeAlias { identifier, r#type } = &node.statement {
+                self.aliases.insert(identifier.clone(), r#type.clone());
+            }
+        }
+
+        while let Some(Node { statement, line, span }) = self.ast.next() {
+            self.line = line;
+            self.builder.at(line, span.0);
+
+            if let Err(mut diagnostic) = self.compile_statement(statement) {
+                // Errors found inside of expressions that don't know where they are point at the whole statement instead.
+                if diagnostic.line == 0 {
+                    diagnostic.line = line;
+                    diagnostic.span = span;
+                }
+
+                self.diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    pub fn compile(&mut self) -> Result<Builder, Vec<Diagnostic>> {
+        self.compile_statements();
+
+        for helper in &self.helpers {
+            self.builder.at(0, 0).function(match helper {
+                Helper::Expect => checked::helper(),
+                Helper::Range => range::helper(),
+                Helper::Entries => entries::helper(),
+            });
+        }
+
+        if self.diagnostics.is_empty() {
+            Ok(self.builder())
+        } else {
+            Err(self.diagnostics.clone())
+        }
     }
 
     pub fn builder(&self) -> Builder {
         self.builder.clone()
     }
+}
+
+/// The value of a number literal, including negative ones such as `-2`.
+fn literal(expression: &Expression) -> Option<f64> {
+    match expression {
+        Expression::Number(n) => Some(*n),
+        Expression::Prefix(Op::Subtract, value) => literal(value).map(|n| -n),
+        _ => None,
+    }
+}
+
+/// Turn the last expression of a block into a `return`, so that the block produces its value. A trailing `if` produces
+/// the value of whichever branch runs.
+fn returning(mut nodes: Vec<Node>) -> Vec<Node> {
+    match nodes.pop() {
+        Some(Node { statement: Statement::Expression { expression }, line, span }) => {
+            nodes.push(Node::new(Statement::Return { expression }, line, span));
+        },
+        Some(Node { statement: Statement::If { condition, then, otherwise }, line, span }) => {
+            nodes.push(Node::new(Statement::If { condition, then: returning(then), otherwise: returning(otherwise) }, line, span));
+        },
+        Some(node) => nodes.push(node),
+        None => {},
+    }
+
+    nodes
+}
+
+/// Whether a branch of an `if` expression is just a value, including an `else if` whose own branches are, so that it
+/// can be compiled to a conditional expression.
+fn simple(nodes: &[Node]) -> bool {
+    match nodes {
+        [Node { statement: Statement::Expression { .. }, .. }] => true,
+        [Node { statement: Statement::If { then, otherwise, .. }, .. }] => simple(then) && simple(otherwise),
+        _ => false,
+    }
+}
+
+/// Whether a block ends with an expression, or with an `if` whose branches all do, so that `returning()` gives it a value.
+fn produces(nodes: &[Node]) -> bool {
+    match nodes.last() {
+        Some(Node { statement: Statement::Expression { .. }, .. }) => true,
+        Some(Node { statement: Statement::If { then, otherwise, .. }, .. }) => produces(then) && produces(otherwise),
+        _ => false,
+    }
+}
+
+/// Whether every chain of `else if`s ends with an `else`, so that an `if` expression always produces a value.
+fn complete(otherwise: &[Node]) -> bool {
+    match otherwise {
+        [] => false,
+        [Node { statement: Statement::If { otherwise, .. }, .. }] => complete(otherwise),
+        _ => true,
+    }
+}
+
+/// Whether an expression reads through a `?.` anywhere along its chain, such as `a?.b.c`, which JavaScript doesn't
+/// allow as the target of an assignment.
+fn optional(expression: &Expression) -> bool {
+    match expression {
+        Expression::OptionalIndex(..) | Expression::OptionalDot(..) => true,
+        Expression::Dot(target, property) => optional(target) || optional(property),
+        Expression::Index(target, _) | Expression::Call(target, _) => optional(target),
+        _ => false,
+    }
+}
+
+/// Compile the pattern of a `let`, a parameter or a loop into a JavaScript destructuring target.
+fn destructure(pattern: &Pattern) -> CompilerResult<JsExpression> {
+    pattern::validate(pattern).and_then(|_| pattern::destructure(pattern)).map_err(Diagnostic::unpositioned)
+}
+
+/// Make sure that a `match` has an arm for any value, and that its patterns can be compiled.
+fn validate(arms: &[Arm], position: Position) -> CompilerResult<()> {
+    for arm in arms {
+        pattern::validate(&arm.pattern).map_err(|message| Diagnostic::at(message, position))?;
+    }
+
+    if ! arms.iter().any(|arm| arm.guard.is_none() && arm.pattern.irrefutable()) {
+        return Err(Diagnostic::at("`match` is missing a default arm, add a `_ => ...` arm to handle any other value", position));
+    }
+
+    Ok(())
+}
+
+/// Add an arm without a condition to the block that holds the arms. There's no `if` to scope the names that it
+/// declares, so an arm that declares any is put in a block of its own, otherwise another arm that binds the same name
+/// would declare it twice.
+fn unconditional(block: &mut Builder, then: Builder) {
+    if then.statements().iter().any(|statement| matches!(statement, JsStatement::Var(_) | JsStatement::Function(_))) {
+        block.block(None, then);
+    } else {
+        block.append(then);
+    }
+}
+
+/// Whether a block would leave early, with a `return`, or a `break` or `continue` for a loop outside of the block.
+fn escapes(nodes: &[Node], loops: &mut Vec<Option<String>>) -> bool {
+    nodes.iter().any(|node| match &node.statement {
+        Statement::Return { .. } => true,
+        Statement::Break { label: None } | Statement::Continue { label: None } => loops.is_empty(),
+        Statement::Break { label } | Statement::Continue { label } => ! loops.contains(label),
+        Statement::If { then, otherwise, .. } => escapes(then, loops) || escapes(otherwise, loops),
+        Statement::While { label, then, .. } | Statement::For { label, then, .. } => {
+            loops.push(label.clone());
+            let escapes = escapes(then, loops);
+            loops.pop();
+
+            escapes
+        },
+        _ => false,
+    })
 }
\ No newline at end of file
@@ -0,0 +1,104 @@
+use tonic_parser::{ParserError, Position, Span};
+use tonic_typeck::TypeError;
+use std::fmt::{Display, Formatter, Result};
+
+/// A `Diagnostic` describes a single problem found while compiling a Tonic program.
+///
+/// The `line` and `span` (start and end column) follow the same conventions as a `Token`. A diagnostic
+/// that can't be tied to a location in the source code has a `line` of `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub line: usize,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, line: usize, span: Span) -> Self {
+        Self { message: message.into(), line, span }
+    }
+
+    pub fn unpositioned(message: impl Into<String>) -> Self {
+        Self::new(message, 0, (0, 0))
+    }
+
+    /// A diagnostic for an expression, which is left unpositioned if the expression doesn't know where it is.
+    pub fn at(message: impl Into<String>, position: Position) -> Self {
+        Self::new(message, position.line, position.span)
+    }
+
+    /// Render the diagnostic in the same style as `rustc`, showing the offending line of source code
+    /// with the span underlined.
+    pub fn render(&self, file: &str, source: &str) -> String {
+        let mut output = format!("error: {}\n", self.message);
+
+        if self.line == 0 {
+            output.push_str(&format!(" --> {}\n", file));
+
+            return output;
+        }
+
+        let code = source.lines().nth(self.line - 1).unwrap_or_default();
+        let start = self.span.0.max(1);
+        let width = self.span.1.saturating_sub(start).max(1);
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        // Tabs are kept in the padding so that the carets line up with the code above them.
+        let padding = code.chars().take(start - 1).map(|c| if c == '\t' { '\t' } else { ' ' }).collect::<String>();
+
+        output.push_str(&format!("{}--> {}:{}:{}\n", gutter, file, self.line, start));
+        output.push_str(&format!("{} |\n", gutter));
+        output.push_str(&format!("{} | {}\n", self.line, code));
+        output.push_str(&format!("{} | {}{}\n", gutter, padding, "^".repeat(width)));
+
+        output
+    }
+}
+
+impl From<ParserError> for Diagnostic {
+    fn from(error: ParserError) -> Self {
+        Self::new(error.to_string(), error.line, error.span)
+    }
+}
+
+impl From<TypeError> for Diagnostic {
+    fn from(error: TypeError) -> Self {
+        Self::new(error.to_string(), error.line, error.span)
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}:{}: {}", self.line, self.span.0, self.message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_source_line_with_carets() {
+        let diagnostic = Diagnostic::new("unexpected token `RightParen`", 2, (9, 10));
+
+        assert_eq!(diagnostic.render("main.tn", "let a = 1\nlet b = )\n"), [
+            "error: unexpected token `RightParen`",
+            " --> main.tn:2:9",
+            "  |",
+            "2 | let b = )",
+            "  |         ^",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn renders_unpositioned_diagnostics() {
+        let diagnostic = Diagnostic::unpositioned("something went wrong");
+
+        assert_eq!(diagnostic.render("main.tn", ""), "error: something went wrong\n --> main.tn\n");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use tonic_js_builder::{Builder, Function, Expression as JsExpression};
+
+/// The name of the runtime helper that `for key, value in` loops iterate over.
+const HELPER: &str = "__tonic_entries";
+
+/// Call the runtime helper with the value being looped over.
+pub(crate) fn call(iterable: JsExpression) -> JsExpression {
+    JsExpression::from((JsExpression::identifier(HELPER), vec![iterable]))
+}
+
+/// The runtime helper used by `call()`. `Object.entries()` would give an array's indexes as strings, so arrays use
+/// their own `entries()` instead:
+///
+/// ```js
+/// function __tonic_entries(value) {
+///     return Array.isArray(value) ? value.entries() : Object.entries(value);
+/// }
+/// ```
+pub(crate) fn helper() -> Function {
+    let id = JsExpression::identifier;
+
+    let mut body = Builder::new();
+    body.return_(Some(JsExpression::conditional(
+        JsExpression::from((JsExpression::dot(id("Array"), id("isArray")), vec![id("value")])),
+        JsExpression::from((JsExpression::dot(id("value"), id("entries")), vec![])),
+        JsExpression::from((JsExpression::dot(id("Object"), id("entries")), vec![id("value")])),
+    )));
+
+    let mut function = Function::new();
+    function
+        .id(HELPER.to_owned())
+        .parameters(vec![id("value")])
+        .body(body);
+
+    function
+}
\ No newline at end of file
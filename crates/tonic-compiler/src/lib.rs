@@ -1,22 +1,322 @@
 use tonic_parser::{parse, Lexer};
-pub use tonic_parser::{Token, TokenKind};
+use tonic_js_builder::Builder;
+use tonic_typeck::check;
+pub use tonic_parser::{Token, TokenKind, Span};
+pub use tonic_js_builder::SourceMap;
+pub use diagnostic::Diagnostic;
 
 mod compiler;
+mod diagnostic;
+mod checked;
+mod range;
+mod entries;
+mod pattern;
 
-pub fn compile(source: &str) -> String {
-    let ast = parse(source).unwrap();
+/// Options that change how Tonic source code is compiled.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    checked: bool,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guard annotated parameters and return values with runtime type assertions, which throw a `TypeError`
+    /// naming the function, parameter and source line when a value doesn't match its annotation.
+    pub fn checked(&mut self, checked: bool) -> &mut Self {
+        self.checked = checked;
+        self
+    }
+}
+
+/// Compile Tonic source code into JavaScript.
+///
+/// Any syntax errors, type errors, or constructs that the compiler doesn't support, are returned as a list of
+/// `Diagnostic`s instead of the compiled code.
+pub fn compile(source: &str) -> Result<String, Vec<Diagnostic>> {
+    Ok(build(source, &Options::default())?.source())
+}
+
+/// Compile Tonic source code into JavaScript, along with a `SourceMap` that points each generated statement
+/// back to the Tonic statement it came from.
+pub fn compile_with_source_map(source: &str) -> Result<(String, SourceMap), Vec<Diagnostic>> {
+    compile_with_options(source, &Options::default())
+}
+
+/// Compile Tonic source code into JavaScript and a `SourceMap`, using the given `Options`.
+pub fn compile_with_options(source: &str, options: &Options) -> Result<(String, SourceMap), Vec<Diagnostic>> {
+    Ok(build(source, options)?.source_with_map())
+}
+
+fn build(source: &str, options: &Options) -> Result<Builder, Vec<Diagnostic>> {
+    let ast = parse(source).map_err(|errors| errors.into_iter().map(Diagnostic::from).collect::<Vec<Diagnostic>>())?;
+
+    check(&ast).map_err(|errors| errors.into_iter().map(Diagnostic::from).collect::<Vec<Diagnostic>>())?;
 
     let mut compiler = compiler::Compiler::new(ast.into_iter());
+    compiler.checked(options.checked);
     compiler.compile()
 }
 
 pub fn lex(source: &str) -> Vec<Token> {
-    let mut lexer = Lexer::new(source);
-    let mut tokens = Vec::new();
+    Lexer::new(source).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    while let Some(token) = lexer.next() {
-        tokens.push(token);
+    #[test]
+    fn source_maps() {
+        let (source, map) = compile_with_source_map("let a = 1\n\nwhile a < 3 {\n    a = a + 1\n}").unwrap();
+
+        assert_eq!(source, "let a = 1;\nwhile (a < 3) {\n    a = a + 1;\n}\n");
+        assert_eq!(map.lookup(0, 0), Some((0, 0)));
+        assert_eq!(map.lookup(1, 0), Some((2, 0)));
+        assert_eq!(map.lookup(2, 0), Some((3, 4)));
+    }
+
+    #[test]
+    fn type_errors() {
+        assert_eq!(compile("let a = 1\nlet b: string = a + 1\nlet c: bool = 1"), Err(vec![
+            Diagnostic::new("mismatched types, expected `bool` but found `number`", 3, (1, 16)),
+        ]));
+    }
+
+    #[test]
+    fn for_loops() {
+        assert_eq!(compile("for i in 0..n {\n    println(i)\n}").unwrap(), "for (let i = 0, i$end = n; i < i$end; i += 1) {\n    println(i);\n}\n");
+        assert!(compile("for k, v in m {}").unwrap().starts_with("for (let [k, v] of __tonic_entries(m)) {}\n\nfunction __tonic_entries(value) {\n    return Array.isArray(value) ? value.entries() : Object.entries(value);\n}"));
+
+        assert_eq!(compile("for i in 10..=0 step -2 {}").unwrap(), "for (let i = 10; i >= 0; i -= 2) {}\n");
+        assert_eq!(compile("for i in 0.. {}").unwrap(), "for (let i = 0; true; i += 1) {}\n");
+        assert!(compile("for i in 0..10 step n {}").unwrap().starts_with("for (let i of __tonic_range(0, 10, n, false)) {}\n\nfunction __tonic_range("));
     }
 
-    tokens
+    #[test]
+    fn ranges() {
+        assert!(compile("let r = 1..=3").unwrap().starts_with("let r = __tonic_range(1, 3, 1, true);\n\nfunction __tonic_range("));
+        assert!(compile("let r = 1..").unwrap().starts_with("let r = __tonic_range(1, Infinity, 1, false);"));
+
+        assert_eq!(compile("items[1..n]").unwrap(), "items.slice(1, n);\n");
+        assert_eq!(compile("items[..=2]").unwrap(), "items.slice(0, 3);\n");
+        assert_eq!(compile("items[1..=n]").unwrap(), "items.slice(1, n + 1 || undefined);\n");
+        assert_eq!(compile("items[1..]").unwrap(), "items.slice(1);\n");
+        assert_eq!(compile("items[1..=-1]").unwrap(), "items.slice(1);\n");
+        assert_eq!(compile("items[..=-2]").unwrap(), "items.slice(0, -1);\n");
+
+        assert_eq!(compile("items[..2 step 2]\nitems[1..] = []\nf(1, items[1..] = [])"), Err(vec![
+            Diagnostic::new("a range with a `step` can't be used to slice", 1, (1, 18)),
+            Diagnostic::new("cannot assign to a slice", 2, (1, 16)),
+            Diagnostic::new("cannot assign to a slice", 3, (6, 21)),
+        ]));
+    }
+
+    #[test]
+    fn matches() {
+        assert_eq!(compile("match x {\n    1 | 2 => a(),\n    [y, _] => b(y),\n    _ => c(),\n}").unwrap(), [
+            "if (x === 1 || x === 2) {",
+            "    a();",
+            "} else if (Array.isArray(x) && x.length === 2) {",
+            "    let y = x[0];",
+            "    b(y);",
+            "} else {",
+            "    c();",
+            "}",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("let y = 1\nmatch x {\n    y => f(y),\n}\nmatch y {\n    [y] => f(y),\n    _ => g(),\n}").unwrap(), [
+            "let y = 1;",
+            "{",
+            "    let y = x;",
+            "    f(y);",
+            "}",
+            "let match$2 = y;",
+            "if (Array.isArray(match$2) && match$2.length === 1) {",
+            "    let y = match$2[0];",
+            "    f(y);",
+            "} else {",
+            "    g();",
+            "}",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("match x {\n    n if n > 0 => f(n),\n    n if n < 0 => g(n),\n    _ => h(),\n}").unwrap(), [
+            "match$1: {",
+            "    {",
+            "        let n = x;",
+            "        if (n > 0) {",
+            "            f(n);",
+            "            break match$1;",
+            "        }",
+            "    }",
+            "    {",
+            "        let n = x;",
+            "        if (n < 0) {",
+            "            g(n);",
+            "            break match$1;",
+            "        }",
+            "    }",
+            "    h();",
+            "}",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("let a = match f() {\n    {\"k\": v} if v => v,\n    _ => 0,\n}").unwrap(), [
+            "let a = ((match$1) => {",
+            "    if (typeof match$1 === \"object\" && match$1 !== null && match$1[\"k\"] !== undefined) {",
+            "        let v = match$1[\"k\"];",
+            "        if (v) {",
+            "            return v;",
+            "        }",
+            "    }",
+            "    return 0;",
+            "})(f());",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("let s = match v {\n    n if n > 0 => \"pos\",\n    n if n < 0 => \"neg\",\n    _ => \"zero\",\n}").unwrap(), [
+            "let s = ((match$1) => {",
+            "    {",
+            "        let n = match$1;",
+            "        if (n > 0) {",
+            "            return \"pos\";",
+            "        }",
+            "    }",
+            "    {",
+            "        let n = match$1;",
+            "        if (n < 0) {",
+            "            return \"neg\";",
+            "        }",
+            "    }",
+            "    return \"zero\";",
+            "})(v);",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("match x {\n    1 => a(),\n}\nmatch x {\n    [a, a] => 1,\n    _ => 2,\n}\nwhile true {\n    let b = match x {\n        _ => {\n            break\n        }\n    }\n}"), Err(vec![
+            Diagnostic::new("`match` is missing a default arm, add a `_ => ...` arm to handle any other value", 1, (1, 6)),
+            Diagnostic::new("`a` is bound more than once in the same pattern", 4, (1, 6)),
+            Diagnostic::new("`return`, `break` and `continue` can't be used inside of a `match` that produces a value", 9, (13, 18)),
+        ]));
+
+        assert_eq!(compile("let y = f(x, match x {\n    1 => 2,\n})"), Err(vec![
+            Diagnostic::new("`match` is missing a default arm, add a `_ => ...` arm to handle any other value", 1, (14, 19)),
+        ]));
+
+        assert_eq!(compile("let a = match x {\n    1 => {},\n    _ => 2,\n}\nlet b = match x {\n    _ => {\n        let c = 1\n    },\n}"), Err(vec![
+            Diagnostic::new("`match` arm doesn't produce a value, end it with an expression", 1, (9, 14)),
+            Diagnostic::new("`match` arm doesn't produce a value, end it with an expression", 5, (9, 14)),
+        ]));
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(compile("let a = [0xFF, 0b11, 1_000, 1e-9, 10n, 0xFFn, -5n]").unwrap(), "let a = [255, 3, 1000, 0.000000001, 10n, 0xFFn, -5n];\n");
+        assert_eq!(compile("match x {\n    -1n => a(),\n    _ => b(),\n}").unwrap(), "if (x === -1n) {\n    a();\n} else {\n    b();\n}\n");
+        assert_eq!(compile("let a = 10px"), Err(vec![Diagnostic::new("malformed number `10px`", 1, (9, 13))]));
+    }
+
+    #[test]
+    fn nulls() {
+        assert_eq!(compile("let a = b?.c?.[0] ?? null\na ??= 1\nlet d = a == null || b != null").unwrap(), [
+            "let a = b?.c?.[0] ?? null;",
+            "a ??= 1;",
+            "let d = a == null || b != null;",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("(a || b) ?? c && d").unwrap(), "(a || b) ?? (c && d);\n");
+
+        assert_eq!(compile("a?.b = 1\na?.[0].c += 2\nlet b = f(a?.b -= 1)"), Err(vec![
+            Diagnostic::new("cannot assign to an optional chain", 1, (1, 9)),
+            Diagnostic::new("cannot assign to an optional chain", 2, (1, 14)),
+            Diagnostic::new("cannot assign to an optional chain", 3, (11, 20)),
+        ]));
+    }
+
+    #[test]
+    fn if_expressions() {
+        assert_eq!(compile("let x = if a > b { a } else if a == b { 0 } else { b }").unwrap(), "let x = a > b ? a : a === b ? 0 : b;\n");
+
+        assert_eq!(compile("let x = if a {\n    f()\n    1\n} else {\n    2\n}").unwrap(), [
+            "let x = (() => {",
+            "    if (a) {",
+            "        f();",
+            "        return 1;",
+            "    } else {",
+            "        return 2;",
+            "    }",
+            "})();",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("let x = if a { 1 }\nlet y = if a { 1 } else if b { 2 }\nwhile true {\n    let z = if a { break } else { 1 }\n}"), Err(vec![
+            Diagnostic::new("`if` is missing an `else` branch, add one so that it produces a value either way", 1, (9, 19)),
+            Diagnostic::new("`if` is missing an `else` branch, add one so that it produces a value either way", 2, (9, 35)),
+            Diagnostic::new("`return`, `break` and `continue` can't be used inside of an `if` that produces a value", 4, (13, 38)),
+        ]));
+
+        assert_eq!(compile("let x = if a { let y = 1 } else { 2 }\nlet y = if a { 1 } else {\n    while b {}\n}\nlet z = if a { if b { 1 } } else { 2 }"), Err(vec![
+            Diagnostic::new("`if` branch doesn't produce a value, end it with an expression", 1, (9, 38)),
+            Diagnostic::new("`if` branch doesn't produce a value, end it with an expression", 2, (9, 11)),
+            Diagnostic::new("`if` branch doesn't produce a value, end it with an expression", 5, (9, 39)),
+        ]));
+    }
+
+    #[test]
+    fn interpolated_strings() {
+        assert_eq!(compile(r#"let greeting = "Hello, {name}! `{"$"}{price}` \{\}""#).unwrap(), "let greeting = `Hello, ${name}! \\`\\$${price}\\` {}`;\n");
+
+        assert_eq!(compile("let a = \"{b\"\nlet c = d").unwrap_err()[0], Diagnostic::new("unclosed `{` in string, use `\\{` to write a brace", 1, (10, 11)));
+        assert_eq!(compile("let a = \"b}\""), Err(vec![Diagnostic::new("unmatched `}` in string, use `\\}` to write a brace", 1, (11, 12))]));
+    }
+
+    #[test]
+    fn destructuring() {
+        assert_eq!(compile("let [a, _, ...rest] = items\nlet {\"name\": name, \"age\": _} = person\nlet _ = f()").unwrap(), [
+            "let [a, , ...rest] = items;",
+            "let { \"name\": name } = person;",
+            "f();",
+            "",
+        ].join("\n"));
+
+        assert_eq!(compile("const [a, b] = pair").unwrap(), "const [a, b] = pair;\n");
+
+        assert_eq!(compile("let [a, 1] = items\nlet [b, b] = items"), Err(vec![
+            Diagnostic::new("only names, `_`, arrays and maps can be destructured, use a `match` to check for other values", 1, (1, 19)),
+            Diagnostic::new("`b` is bound more than once in the same pattern", 2, (1, 19)),
+        ]));
+
+        assert_eq!(compile("let f = fn (_, [_, a], _) -> a").unwrap(), "let f = (_$1, [, a], _$3) => {\n    return a;\n};\n");
+
+        assert_eq!(compile("fn f(a, [a]) {}\nlet g = fn (b, {\"k\": b}) {}"), Err(vec![
+            Diagnostic::new("`a` is bound by more than one parameter of `f`", 1, (1, 16)),
+            Diagnostic::new("`b` is bound by more than one parameter of `closure`", 2, (1, 28)),
+        ]));
+    }
+
+    #[test]
+    fn checked() {
+        let mut options = Options::new();
+        options.checked(true);
+
+        let (source, _) = compile_with_options("fn double(n: number): number {\n    return n * 2\n}", &options).unwrap();
+
+        assert!(source.starts_with(r#"function double(n) {
+    __tonic_expect((value) => {
+        return typeof value === "number";
+    }, n, "parameter `n` of `double`", "number", 1);
+    return __tonic_expect((value) => {
+        return typeof value === "number";
+    }, n * 2, "return value of `double`", "number", 2);
+}
+
+function __tonic_expect(valid, value, name, expected, line) {"#));
+
+        assert!(! compile("fn double(n: number): number {\n    return n * 2\n}").unwrap().contains("__tonic_expect"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+use tonic_parser::Pattern;
+use tonic_js_builder::Expression as JsExpression;
+
+/// Check that a pattern can be compiled, returning the problem if it can't.
+pub(crate) fn validate(pattern: &Pattern) -> Result<(), String> {
+    let mut names = pattern.bindings();
+    names.sort_unstable();
+
+    if let Some(name) = names.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(format!("`{}` is bound more than once in the same pattern", name[0]));
+    }
+
+    match pattern {
+        Pattern::Or(patterns) if patterns.iter().any(|p| ! p.bindings().is_empty()) => Err("patterns joined with `|` can't bind names".to_owned()),
+        Pattern::Or(items) | Pattern::Array(items) => items.iter().try_for_each(validate),
+        Pattern::Map(members) => members.iter().try_for_each(|(_, pattern)| validate(pattern)),
+        _ => Ok(()),
+    }
+}
+
+/// Build the condition that checks whether `subject` matches a pattern, or `None` if the pattern matches anything.
+pub(crate) fn condition(pattern: &Pattern, subject: &JsExpression) -> Option<JsExpression> {
+    let all = |conditions: Vec<JsExpression>| conditions.into_iter().reduce(|left, right| JsExpression::infix(left, "&&", right));
+
+    match pattern {
+        Pattern::Wildcard | Pattern::Binding(_) | Pattern::Rest(_) => None,
+        Pattern::String(s) => Some(JsExpression::infix(subject.clone(), "===", s.as_str().into())),
+        Pattern::Number(n) => Some(JsExpression::infix(subject.clone(), "===", (*n).into())),
+        Pattern::BigInt(n) => Some(JsExpression::infix(subject.clone(), "===", match n.strip_prefix('-') {
+            Some(n) => JsExpression::Prefix("-".to_owned(), Box::new(JsExpression::bigint(n))),
+            None => JsExpression::bigint(n.as_str()),
+        })),
+        Pattern::Bool(b) => Some(JsExpression::infix(subject.clone(), "===", (*b).into())),
+        Pattern::Null => Some(JsExpression::infix(subject.clone(), "==", JsExpression::null())),
+        Pattern::Or(patterns) => patterns.iter()
+            .map(|pattern| condition(pattern, subject))
+            .collect::<Option<Vec<JsExpression>>>()?
+            .into_iter()
+            .reduce(|left, right| JsExpression::infix(left, "||", right)),
+        Pattern::Array(items) => {
+            // With a rest pattern at the end, the array only needs enough items for the patterns before it.
+            let (length, comparison) = match items.last() {
+                Some(Pattern::Rest(_)) => (items.len() - 1, ">="),
+                _ => (items.len(), "==="),
+            };
+
+            let mut conditions = vec![
+                JsExpression::from((
+                    JsExpression::dot(JsExpression::identifier("Array"), JsExpression::identifier("isArray")),
+                    vec![subject.clone()],
+                )),
+                JsExpression::infix(
+                    JsExpression::dot(subject.clone(), JsExpression::identifier("length")),
+                    comparison,
+                    (length as i64).into(),
+                ),
+            ];
+
+            for (i, item) in items.iter().enumerate() {
+                conditions.extend(condition(item, &JsExpression::index(subject.clone(), (i as i64).into())));
+            }
+
+            all(conditions)
+        },
+        Pattern::Map(members) => {
+            let mut conditions = vec![
+                JsExpression::infix(JsExpression::Prefix("typeof".to_owned(), Box::new(subject.clone())), "===", "object".into()),
+                JsExpression::infix(subject.clone(), "!==", JsExpression::null()),
+            ];
+
+            // Keys that are bound to a name, or ignored with `_`, still have to be there.
+            for (key, pattern) in members {
+                let value = JsExpression::index(subject.clone(), key.as_str().into());
+
+                conditions.push(condition(pattern, &value).unwrap_or_else(|| JsExpression::infix(value, "!==", JsExpression::identifier("undefined"))));
+            }
+
+            all(conditions)
+        },
+    }
+}
+
+/// The names that a pattern binds, along with the part of the subject that each one is bound to.
+pub(crate) fn bindings(pattern: &Pattern, subject: &JsExpression) -> Vec<(String, JsExpression)> {
+    match pattern {
+        Pattern::Binding(name) => vec![(name.clone(), subject.clone())],
+        Pattern::Array(items) => items.iter()
+            .enumerate()
+            .flat_map(|(i, item)| match item {
+                Pattern::Rest(Some(name)) => vec![(name.clone(), JsExpression::from((
+                    JsExpression::dot(subject.clone(), JsExpression::identifier("slice")),
+                    vec![(i as i64).into()],
+                )))],
+                item => bindings(item, &JsExpression::index(subject.clone(), (i as i64).into())),
+            })
+            .collect(),
+        Pattern::Map(members) => members.iter()
+            .flat_map(|(key, pattern)| bindings(pattern, &JsExpression::index(subject.clone(), key.as_str().into())))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Build the JavaScript destructuring target for the pattern of a `let`, a parameter or a loop. Unlike in a `match`,
+/// nothing is checked, so missing items and keys are bound to `undefined` and extra ones are ignored.
+pub(crate) fn destructure(pattern: &Pattern) -> Result<JsExpression, String> {
+    Ok(match pattern {
+        // A leading `_` still needs a name, but one inside of an array or map can just be left out.
+        Pattern::Wildcard => JsExpression::identifier("_"),
+        Pattern::Binding(name) => JsExpression::identifier(name.as_str()),
+        Pattern::Array(items) => JsExpression::Array(items.iter()
+            .filter(|item| ! matches!(item, Pattern::Rest(None)))
+            .map(|item| match item {
+                // An empty identifier leaves a hole, such as `[, b]`.
+                Pattern::Wildcard => Ok(JsExpression::identifier("")),
+                Pattern::Rest(Some(name)) => Ok(JsExpression::Prefix("...".to_owned(), Box::new(JsExpression::identifier(name.as_str())))),
+                item => destructure(item),
+            })
+            .collect::<Result<Vec<JsExpression>, String>>()?),
+        Pattern::Map(members) => JsExpression::object(members.iter()
+            .filter(|(_, pattern)| *pattern != Pattern::Wildcard)
+            .map(|(key, pattern)| Ok((key.clone(), destructure(pattern)?)))
+            .collect::<Result<Vec<(String, JsExpression)>, String>>()?),
+        Pattern::Rest(_) => unreachable!("the parser only allows a rest pattern inside of an array"),
+        Pattern::String(_) | Pattern::Number(_) | Pattern::BigInt(_) | Pattern::Bool(_) | Pattern::Null | Pattern::Or(_) => {
+            return Err("only names, `_`, arrays and maps can be destructured, use a `match` to check for other values".to_owned())
+        },
+    })
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+use tonic_js_builder::{Builder, For, Function, Var, Expression as JsExpression};
+
+/// The name of the runtime helper that ranges are compiled to, unless they can be lowered to a counted loop or a slice.
+const HELPER: &str = "__tonic_range";
+
+/// Call the runtime helper. Missing parts of the range are filled in by the caller.
+pub(crate) fn call(start: JsExpression, end: JsExpression, step: JsExpression, inclusive: bool) -> JsExpression {
+    JsExpression::from((JsExpression::identifier(HELPER), vec![start, end, step, inclusive.into()]))
+}
+
+/// The runtime helper used by `call()`. It returns an iterable object, rather than a generator, so that the same range
+/// can be looped over more than once:
+///
+/// ```js
+/// function __tonic_range(start, end, step, inclusive) {
+///     function* values() {
+///         for (let i = start; step > 0 && (i < end || inclusive && i === end) || ...; i += step) {
+///             yield i;
+///         }
+///     }
+///     let range = {};
+///     range[Symbol.iterator] = values;
+///     return range;
+/// }
+/// ```
+pub(crate) fn helper() -> Function {
+    let id = JsExpression::identifier;
+
+    let reaches = |comparison: &str| JsExpression::infix(
+        JsExpression::infix(id("i"), comparison, id("end")),
+        "||",
+        JsExpression::infix(id("inclusive"), "&&", JsExpression::infix(id("i"), "===", id("end"))),
+    );
+
+    let condition = JsExpression::infix(
+        JsExpression::infix(JsExpression::infix(id("step"), ">", 0.into()), "&&", reaches("<")),
+        "||",
+        JsExpression::infix(JsExpression::infix(id("step"), "<", 0.into()), "&&", reaches(">")),
+    );
+
+    let mut yields = Builder::new();
+    yields.expression(JsExpression::Prefix("yield".to_owned(), Box::new(id("i"))));
+
+    let mut loop_ = For::counted(vec![("i".to_owned(), id("start"))], condition, JsExpression::infix(id("i"), "+=", id("step")));
+    loop_.then(yields);
+
+    let mut values_body = Builder::new();
+    values_body.for_loop(loop_);
+
+    let mut values = Function::new();
+    values.id("values".to_owned()).generator().body(values_body);
+
+    let mut range = Var::new();
+    range.id("range".to_owned()).as_let().value(JsExpression::object(vec![]));
+
+    let mut body = Builder::new();
+    body
+        .function(values)
+        .var(range)
+        .expression(JsExpression::infix(
+            JsExpression::index(id("range"), JsExpression::dot(id("Symbol"), id("iterator"))),
+            "=",
+            id("values"),
+        ))
+        .return_(Some(id("range")));
+
+    let mut function = Function::new();
+    function
+        .id(HELPER.to_owned())
+        .parameters(["start", "end", "step", "inclusive"].into_iter().map(id).collect())
+        .body(body);
+
+    function
+}
\ No newline at end of file
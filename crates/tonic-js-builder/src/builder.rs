@@ -1,85 +1,130 @@
-use crate::{Var, Expression, Function, IfElse, While};
+use crate::{Var, Expression, Function, IfElse, While, For, Statement, Printer, SourceMap};
 use std::fmt::{Result, Formatter, Display};
 
 #[derive(Debug, Clone)]
 pub struct Builder {
-    source: String,
+    statements: Vec<Statement>,
+    origins: Vec<Option<(usize, usize)>>,
+    origin: Option<(usize, usize)>,
 }
 
 impl Builder {
     pub fn new() -> Self {
         Self {
-            source: String::default(),
+            statements: Vec::new(),
+            origins: Vec::new(),
+            origin: None,
         }
     }
 
     pub fn import(&mut self, items: Vec<String>, module: String) -> &mut Self {
-        self.source.push_str("import { ");
-        self.source.push_str(&items.join(", "));
-        self.source.push_str(" } from \"");
-        self.source.push_str(&module);
-        self.source.push_str("\";\n");
-
-        self
+        self.statement(Statement::Import { items, module })
     }
 
     pub fn var(&mut self, var: Var) -> &mut Self {
-        self.source.push_str(&var.to_string());
-        self
+        self.statement(Statement::Var(var))
     }
 
-    pub fn function(&mut self, function: Function) -> &mut Self {        
-        self.source.push_str(&function.to_string());
-        self
+    pub fn function(&mut self, function: Function) -> &mut Self {
+        self.statement(Statement::Function(function))
     }
 
     pub fn conditional(&mut self, if_else: IfElse) -> &mut Self {
-        self.source.push_str(&if_else.to_string());
-        self
+        self.statement(Statement::If(if_else))
     }
 
     pub fn while_loop(&mut self, while_: While) -> &mut Self {
-        self.source.push_str(&while_.to_string());
-        self
+        self.statement(Statement::While(while_))
+    }
+
+    pub fn for_loop(&mut self, for_: For) -> &mut Self {
+        self.statement(Statement::For(for_))
+    }
+
+    pub fn block(&mut self, label: Option<String>, body: Builder) -> &mut Self {
+        self.statement(Statement::Block(label, body))
     }
 
     pub fn return_(&mut self, expression: Option<Expression>) -> &mut Self {
-        self.source.push_str("return");
+        self.statement(Statement::Return(expression))
+    }
 
-        if let Some(expression) = expression {
-            self.source.push(' ');
-            self.source.push_str(&expression.to_string());
-        }
+    pub fn break_(&mut self, label: Option<String>) -> &mut Self {
+        self.statement(Statement::Break(label))
+    }
+
+    pub fn continue_(&mut self, label: Option<String>) -> &mut Self {
+        self.statement(Statement::Continue(label))
+    }
 
-        self.source.push(';');
+    pub fn throw(&mut self, expression: Expression) -> &mut Self {
+        self.statement(Statement::Throw(expression))
+    }
 
+    pub fn expression(&mut self, expression: Expression) -> &mut Self {
+        self.statement(Statement::Expression(expression))
+    }
+
+    pub fn statement(&mut self, statement: Statement) -> &mut Self {
+        self.statements.push(statement);
+        self.origins.push(self.origin);
         self
     }
 
-    pub fn break_(&mut self) -> &mut Self {
-        self.source.push_str("break;");
+    /// Move the statements from another `Builder` onto the end of this one, keeping their origins.
+    pub fn append(&mut self, mut other: Builder) -> &mut Self {
+        self.statements.append(&mut other.statements);
+        self.origins.append(&mut other.origins);
         self
     }
 
-    pub fn continue_(&mut self) -> &mut Self {
-        self.source.push_str("continue;");
+    /// Set the position in the original source code that the next statements were compiled from.
+    /// 
+    /// The `line` and `column` are 1-based, and a `line` of `0` means that the statements don't have an origin.
+    pub fn at(&mut self, line: usize, column: usize) -> &mut Self {
+        self.origin = if line == 0 { None } else { Some((line, column.max(1))) };
         self
     }
 
-    pub fn expression(&mut self, expression: Expression) -> &mut Self {
-        self.source.push_str(&expression.to_string());
-        self.source.push(';');
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
 
-        self
+    /// The original position of each statement, in the same order as `statements()`.
+    pub fn origins(&self) -> &[Option<(usize, usize)>] {
+        &self.origins
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
     }
 
+    /// Print the statements as readable, indented JavaScript.
     pub fn source(&self) -> String {
-        self.source.clone()
+        Printer::readable().print(self)
+    }
+
+    /// Print the statements as compact JavaScript, without any unnecessary whitespace.
+    pub fn compact(&self) -> String {
+        Printer::compact().print(self)
+    }
+
+    /// Print the statements as readable JavaScript, along with a `SourceMap` back to the original source code.
+    pub fn source_with_map(&self) -> (String, SourceMap) {
+        let mut printer = Printer::readable();
+        printer.statements(self);
+        printer.finish_with_map()
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Display for Builder {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", self.source)
+        write!(f, "{}", self.source())
     }
 }
\ No newline at end of file
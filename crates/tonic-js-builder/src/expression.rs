@@ -1,29 +1,49 @@
 use std::fmt::{Display, Formatter, Result};
-use std::collections::HashMap;
-use crate::Builder;
+use crate::{Builder, Printer};
 
 #[derive(Debug, Clone)]
 pub enum Expression {
     String(String),
+    /// A template literal, such as `` `Hello, ${name}!` ``, which always has one more string than it has expressions.
+    Template(Vec<String>, Vec<Self>),
     Number(f64),
+    /// A `BigInt` literal, such as `10n`, holding the digits without the `n`.
+    BigInt(String),
     Bool(bool),
     Null,
     Array(Vec<Self>),
-    Object(HashMap<String, Self>),
+    Object(Vec<(String, Self)>),
     Index(Box<Self>, Box<Self>),
     Dot(Box<Self>, Box<Self>),
+    /// `target?.[index]`
+    OptionalIndex(Box<Self>, Box<Self>),
+    /// `target?.property`
+    OptionalDot(Box<Self>, Box<Self>),
     Infix(Box<Self>, String, Box<Self>),
     Prefix(String, Box<Self>),
     Call(Box<Self>, Vec<Self>),
+    /// `test ? then : otherwise`
+    Conditional(Box<Self>, Box<Self>, Box<Self>),
     Identifier(String),
     Closure(Vec<Self>, Builder),
 }
 
 impl Expression {
+    /// Build a template literal from its parts, where each expression is embedded between the strings on either side of it.
+    pub fn template(strings: Vec<String>, expressions: Vec<Self>) -> Self {
+        debug_assert_eq!(strings.len(), expressions.len() + 1);
+
+        Self::Template(strings, expressions)
+    }
+
     pub fn number(n: f64) -> Self {
         Self::Number(n)
     }
 
+    pub fn bigint(digits: impl Into<String>) -> Self {
+        Self::BigInt(digits.into())
+    }
+
     pub fn string(s: impl Into<String>) -> Self {
         Self::String(s.into())
     }
@@ -52,11 +72,23 @@ impl Expression {
         Self::Dot(Box::new(target), Box::new(property))
     }
 
+    pub fn optional_index(target: Expression, index: Expression) -> Self {
+        Self::OptionalIndex(Box::new(target), Box::new(index))
+    }
+
+    pub fn optional_dot(target: Expression, property: Expression) -> Self {
+        Self::OptionalDot(Box::new(target), Box::new(property))
+    }
+
+    pub fn conditional(test: Expression, then: Expression, otherwise: Expression) -> Self {
+        Self::Conditional(Box::new(test), Box::new(then), Box::new(otherwise))
+    }
+
     pub fn closure(parameters: Vec<Self>, body: Builder) -> Self {
         Self::Closure(parameters, body)
     }
 
-    pub fn object(members: HashMap<String, Self>) -> Self {
+    pub fn object(members: Vec<(String, Self)>) -> Self {
         Self::Object(members)
     }
 }
@@ -129,34 +161,10 @@ impl From<(Box<Expression>, Vec<Expression>)> for Expression {
 
 impl Display for Expression {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", match self {
-            Expression::String(s) => format!(r##""{}""##, s),
-            Expression::Number(n) => n.to_string(),
-            Expression::Bool(b) => b.to_string(),
-            Expression::Null => "null".into(),
-            Expression::Array(items) => format!("[{}]", items.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(", ")),
-            Expression::Object(members) => {
-                let mut starter = String::from("{\n");
-
-                for (key, value) in members {
-                    starter.push_str(&format!("\"{}\": {},\n", key, value));
-                }
-
-                starter.push_str("\n}");
-                starter
-            },
-            Expression::Index(target, index) => format!("{}[{}]", *target, *index),
-            Expression::Dot(target, index) => format!("{}.{}", *target, *index),
-            Expression::Identifier(i) => i.to_string(),
-            Expression::Infix(left, op, right) => format!("{} {} {}", *left, op, *right),
-            Expression::Prefix(op, right) => format!("{} {}", op, *right),
-            Expression::Call(callable, parameters) => format!("{}({})", *callable, parameters.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(", ")),
-            Expression::Closure(parameters, body) => format!("({}) => {{\n{}\n}}",
-                parameters.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(", "),
-                body
-            ),
-            _ => unimplemented!()
-        })
+        let mut printer = Printer::readable();
+        printer.expression(self);
+
+        write!(f, "{}", printer.finish())
     }
 }
 
@@ -167,6 +175,19 @@ mod tests {
     #[test]
     fn strings() {
         assert_eq!(r##""Hello!""##, Expression::from("Hello!").to_string().as_str());
+        assert_eq!(r##""say \"hi\"\\n""##, Expression::from("say \"hi\"\\n").to_string().as_str());
+        assert_eq!(r##""a\nb\r\tc\x00d\x1b\x7f""##, Expression::from("a\nb\r\tc\0d\u{1b}\u{7f}").to_string().as_str());
+        assert_eq!(r##""\u2028\u2029é""##, Expression::from("\u{2028}\u{2029}é").to_string().as_str());
+    }
+
+    #[test]
+    fn templates() {
+        let template = Expression::template(
+            vec!["Hello, ".to_owned(), "! `${}` costs $".to_owned(), "\\\n".to_owned()],
+            vec![Expression::identifier("name"), Expression::infix(Expression::from(1), "+", Expression::from(2))],
+        );
+
+        assert_eq!(r##"`Hello, ${name}! \`\${}\` costs \$${1 + 2}\\\n`"##, template.to_string().as_str());
     }
 
     #[test]
@@ -193,10 +214,13 @@ mod tests {
 
     #[test]
     fn objects() {
-        let mut members = HashMap::new();
-        members.insert("foo".to_owned(), Expression::String("bar".to_owned()));
+        let members = vec![("foo".to_owned(), Expression::String("bar".to_owned()))];
 
-        assert_eq!("{\n\"foo\": \"bar\",\n\n}", Expression::object(members).to_string().as_str());
+        assert_eq!("{\n    \"foo\": \"bar\",\n}", Expression::object(members).to_string().as_str());
+
+        let members = vec![("\"quoted\"\n".to_owned(), Expression::from(1))];
+
+        assert_eq!("{\n    \"\\\"quoted\\\"\\n\": 1,\n}", Expression::object(members).to_string().as_str());
     }
 
     #[test]
@@ -216,6 +240,32 @@ mod tests {
         assert_eq!("1 + 2", Expression::from((Expression::from(1), "+".to_string(), Expression::from(2))).to_string().as_str());
     }
 
+    #[test]
+    fn parentheses() {
+        let sum = || Expression::infix(Expression::from(1), "+", Expression::from(2));
+
+        assert_eq!("(1 + 2) * 3", Expression::infix(sum(), "*", Expression::from(3)).to_string().as_str());
+        assert_eq!("3 * (1 + 2)", Expression::infix(Expression::from(3), "*", sum()).to_string().as_str());
+        assert_eq!("1 + 2 + 3", Expression::infix(sum(), "+", Expression::from(3)).to_string().as_str());
+        assert_eq!("3 - (1 + 2)", Expression::infix(Expression::from(3), "-", sum()).to_string().as_str());
+        assert_eq!("2 ** 3 ** 2", Expression::infix(Expression::from(2), "**", Expression::infix(Expression::from(3), "**", Expression::from(2))).to_string().as_str());
+        assert_eq!("(2 ** 3) ** 2", Expression::infix(Expression::infix(Expression::from(2), "**", Expression::from(3)), "**", Expression::from(2)).to_string().as_str());
+        assert_eq!("(1 + 2).foo", Expression::dot(sum(), Expression::identifier("foo")).to_string().as_str());
+        assert_eq!("(1).foo", Expression::dot(Expression::from(1), Expression::identifier("foo")).to_string().as_str());
+    }
+
+    #[test]
+    fn prefix() {
+        let negate = |e| Expression::Prefix("-".to_owned(), Box::new(e));
+
+        assert_eq!("-x", negate(Expression::identifier("x")).to_string().as_str());
+        assert_eq!("-(-x)", negate(negate(Expression::identifier("x"))).to_string().as_str());
+        assert_eq!("-(-1)", negate(Expression::from(-1)).to_string().as_str());
+        assert_eq!("-(1 + 2)", negate(Expression::infix(Expression::from(1), "+", Expression::from(2))).to_string().as_str());
+        assert_eq!("(-2) ** 2", Expression::infix(negate(Expression::from(2)), "**", Expression::from(2)).to_string().as_str());
+        assert_eq!("!a.b", Expression::Prefix("!".to_owned(), Box::new(Expression::dot(Expression::identifier("a"), Expression::identifier("b")))).to_string().as_str());
+    }
+
     #[test]
     fn calls() {
         assert_eq!("foo()", Expression::from(
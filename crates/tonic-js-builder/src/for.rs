@@ -0,0 +1,46 @@
+use crate::{Expression, Builder};
+
+#[derive(Debug, Clone)]
+pub(crate) enum Head {
+    /// `for (let binding of iterable)`
+    Of(Expression, Expression),
+    /// `for (let a = 0, b = 10; condition; update)`
+    Counted(Vec<(String, Expression)>, Expression, Expression),
+}
+
+#[derive(Debug, Clone)]
+pub struct For {
+    pub(crate) label: Option<String>,
+    pub(crate) head: Head,
+    pub(crate) then: Builder,
+}
+
+impl For {
+    /// Loop over the values of an iterable. The binding can be an identifier or an array pattern, such as `[key, value]`.
+    pub fn of(binding: Expression, iterable: Expression) -> Self {
+        Self {
+            label: None,
+            head: Head::Of(binding, iterable),
+            then: Builder::new(),
+        }
+    }
+
+    /// A classic counted loop, where each of the `declarations` is declared with `let` before the first iteration.
+    pub fn counted(declarations: Vec<(String, Expression)>, condition: Expression, update: Expression) -> Self {
+        Self {
+            label: None,
+            head: Head::Counted(declarations, condition, update),
+            then: Builder::new(),
+        }
+    }
+
+    pub fn label(&mut self, label: String) -> &mut Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn then(&mut self, then: Builder) -> &mut Self {
+        self.then = then;
+        self
+    }
+}
\ No newline at end of file
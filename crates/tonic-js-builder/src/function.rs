@@ -1,11 +1,11 @@
 use crate::{Expression, Builder};
-use std::fmt::{Display, Formatter, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Function {
-    id: String,
-    parameters: Vec<Expression>,
-    body: Builder,
+    pub(crate) id: String,
+    pub(crate) parameters: Vec<Expression>,
+    pub(crate) body: Builder,
+    pub(crate) generator: bool,
 }
 
 impl Function {
@@ -14,6 +14,7 @@ impl Function {
             id: String::new(),
             parameters: Vec::new(),
             body: Builder::new(),
+            generator: false,
         }
     }
 
@@ -31,14 +32,16 @@ impl Function {
         self.body = body;
         self
     }
+
+    /// Turn the function into a generator, `function*`, so that its body can `yield` values.
+    pub fn generator(&mut self) -> &mut Self {
+        self.generator = true;
+        self
+    }
 }
 
-impl Display for Function {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "function {}({}) {{\n{}\n}}\n\n",
-            self.id,
-            self.parameters.clone().into_iter().map(|p| p.to_string()).collect::<Vec<String>>().join(", "),
-            self.body
-        )
+impl Default for Function {
+    fn default() -> Self {
+        Self::new()
     }
 }
\ No newline at end of file
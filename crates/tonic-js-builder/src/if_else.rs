@@ -1,11 +1,10 @@
 use crate::{Expression, Builder};
-use std::fmt::{Display, Formatter, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct IfElse {
-    condition: Expression,
-    then: Builder,
-    otherwise: Option<Builder>,
+    pub(crate) condition: Expression,
+    pub(crate) then: Builder,
+    pub(crate) otherwise: Option<Builder>,
 }
 
 impl IfElse {
@@ -31,18 +30,4 @@ impl IfElse {
         self.otherwise = Some(otherwise);
         self
     }
-}
-
-impl Display for IfElse {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "if ({}) {{\n{}\n}}{}\n",
-            self.condition,
-            self.then,
-            if let Some(otherwise) = &self.otherwise {
-                format!(" else {{\n{}\n}}", otherwise)
-            } else {
-                "".to_owned()
-            }
-        )
-    }
 }
\ No newline at end of file
@@ -4,10 +4,18 @@ mod expression;
 mod function;
 mod if_else;
 mod r#while;
+mod r#for;
+mod statement;
+mod printer;
+mod source_map;
 
 pub use var::Var;
 pub use expression::Expression;
 pub use builder::Builder;
 pub use function::Function;
 pub use if_else::IfElse;
-pub use r#while::While;
\ No newline at end of file
+pub use r#while::While;
+pub use r#for::For;
+pub use statement::Statement;
+pub use printer::Printer;
+pub use source_map::{SourceMap, Mapping};
\ No newline at end of file
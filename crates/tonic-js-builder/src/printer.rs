@@ -0,0 +1,830 @@
+use crate::{Builder, Statement, Expression, Var, Function, IfElse, While, For, SourceMap, Mapping};
+use crate::r#for::Head;
+
+const INDENTATION: &str = "    ";
+
+// JavaScript operator precedence, from the loosest binding to the tightest. Binary operators sit in between
+// `CONDITIONAL` and `UNARY`, see `infix_precedence()`.
+const ASSIGNMENT: u8 = 2;
+const CONDITIONAL: u8 = 3;
+const UNARY: u8 = 15;
+const POSTFIX: u8 = 16;
+const MEMBER: u8 = 18;
+const PRIMARY: u8 = 20;
+
+/// The `Printer` turns the statements held by a `Builder` into JavaScript source code.
+/// 
+/// The readable style puts every statement on its own line and indents nested blocks, whereas the
+/// compact style leaves out any whitespace that JavaScript doesn't need.
+/// 
+/// While printing, the `Printer` keeps track of its position in the output so that it can record a `SourceMap`
+/// for statements that have an origin.
+#[derive(Debug)]
+pub struct Printer {
+    compact: bool,
+    indent: usize,
+    output: String,
+    line: usize,
+    column: usize,
+    map: SourceMap,
+}
+
+impl Printer {
+    pub fn readable() -> Self {
+        Self {
+            compact: false,
+            indent: 0,
+            output: String::new(),
+            line: 0,
+            column: 0,
+            map: SourceMap::new(),
+        }
+    }
+
+    pub fn compact() -> Self {
+        Self {
+            compact: true,
+            ..Self::readable()
+        }
+    }
+
+    pub fn print(mut self, builder: &Builder) -> String {
+        self.statements(builder);
+        self.finish()
+    }
+
+    pub fn finish(self) -> String {
+        self.output
+    }
+
+    pub fn finish_with_map(self) -> (String, SourceMap) {
+        (self.output, self.map)
+    }
+
+    pub fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Import { items, module } => {
+                self.write("import");
+                self.space();
+                self.write("{");
+                self.space();
+                self.write(&items.join(if self.compact { "," } else { ", " }));
+                self.space();
+                self.write("}");
+                self.space();
+                self.write("from");
+                self.space();
+                self.string(module);
+                self.write(";");
+            },
+            Statement::Var(var) => self.var(var),
+            Statement::Function(function) => self.function(function),
+            Statement::If(if_else) => self.if_else(if_else),
+            Statement::While(while_) => self.while_loop(while_),
+            Statement::For(for_) => self.for_loop(for_),
+            Statement::Block(label, body) => {
+                if let Some(label) = label {
+                    self.write(label);
+                    self.write(":");
+                    self.space();
+                }
+
+                self.block(body);
+            },
+            Statement::Return(expression) => {
+                self.write("return");
+
+                if let Some(expression) = expression {
+                    self.write(" ");
+                    self.expression(expression);
+                }
+
+                self.write(";");
+            },
+            Statement::Break(label) => self.jump("break", label),
+            Statement::Continue(label) => self.jump("continue", label),
+            Statement::Throw(expression) => {
+                self.write("throw ");
+                self.expression(expression);
+                self.write(";");
+            },
+            Statement::Expression(expression) => {
+                // A statement that starts with `{` would be treated as a block, so object literals need wrapping.
+                if starts_with_object(expression) {
+                    self.write("(");
+                    self.expression(expression);
+                    self.write(")");
+                } else {
+                    self.expression(expression);
+                }
+
+                self.write(";");
+            },
+        }
+    }
+
+    pub fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::String(s) => self.string(s),
+            Expression::Template(strings, expressions) => {
+                self.write("`");
+
+                for (i, string) in strings.iter().enumerate() {
+                    self.write(&escape(string, '`'));
+
+                    if let Some(expression) = expressions.get(i) {
+                        self.write("${");
+                        self.expression(expression);
+                        self.write("}");
+                    }
+                }
+
+                self.write("`");
+            },
+            Expression::Number(n) => self.write(&n.to_string()),
+            Expression::BigInt(n) => self.write(&format!("{}n", n)),
+            Expression::Bool(b) => self.write(&b.to_string()),
+            Expression::Null => self.write("null"),
+            Expression::Array(items) => {
+                self.write("[");
+                self.list(items);
+                self.write("]");
+            },
+            Expression::Object(members) => self.object(members),
+            Expression::Index(target, index) => {
+                self.operand(target, MEMBER);
+                self.write("[");
+                self.expression(index);
+                self.write("]");
+            },
+            Expression::Dot(target, property) => {
+                // A number followed by `.` would be read as a decimal point, so `1.toString()` needs wrapping.
+                self.operand(target, if matches!(**target, Expression::Number(_)) { u8::MAX } else { MEMBER });
+                self.write(".");
+                self.expression(property);
+            },
+            Expression::OptionalIndex(target, index) => {
+                self.operand(target, MEMBER);
+                self.write("?.[");
+                self.expression(index);
+                self.write("]");
+            },
+            Expression::OptionalDot(target, property) => {
+                self.operand(target, MEMBER);
+                self.write("?.");
+                self.expression(property);
+            },
+            Expression::Identifier(i) => self.write(i),
+            Expression::Infix(left, op, right) => {
+                let (precedence, right_associative) = infix_precedence(op);
+                let (left_minimum, right_minimum) = if right_associative {
+                    (precedence + 1, precedence)
+                } else {
+                    (precedence, precedence + 1)
+                };
+
+                // The left operand of `**` can't be a unary expression, so `(-2) ** 2` keeps its parentheses.
+                self.operand(left, if op == "**" { POSTFIX } else if mixes_nullish(op, left) { u8::MAX } else { left_minimum });
+                self.write(&format!(" {} ", op));
+                self.operand(right, if mixes_nullish(op, right) { u8::MAX } else { right_minimum });
+            },
+            Expression::Prefix(op, right) => {
+                self.write(op);
+
+                if op.chars().all(char::is_alphabetic) {
+                    self.write(" ");
+                }
+
+                // Printing `-(-x)` as `--x` would turn it into a decrement.
+                self.operand(right, if repeats_sign(op, right) { u8::MAX } else { UNARY });
+            },
+            Expression::Call(callable, arguments) => {
+                self.operand(callable, MEMBER);
+                self.write("(");
+                self.list(arguments);
+                self.write(")");
+            },
+            Expression::Conditional(test, then, otherwise) => {
+                self.operand(test, CONDITIONAL + 1);
+                self.write(" ? ");
+                self.operand(then, ASSIGNMENT);
+                self.write(" : ");
+                self.operand(otherwise, ASSIGNMENT);
+            },
+            Expression::Closure(parameters, body) => {
+                self.write("(");
+                self.bindings(parameters);
+                self.write(")");
+                self.space();
+                self.write("=>");
+                self.space();
+                self.block(body);
+            },
+        }
+    }
+
+    pub fn statements(&mut self, builder: &Builder) {
+        let statements = builder.statements();
+
+        for (i, (statement, origin)) in statements.iter().zip(builder.origins()).enumerate() {
+            if i > 0 && ! self.compact && separated(&statements[i - 1], statement) {
+                self.write("\n");
+            }
+
+            self.indentation();
+
+            if let Some((line, column)) = origin {
+                self.map.add(Mapping { generated: (self.line, self.column), original: (line - 1, column - 1) });
+            }
+
+            self.statement(statement);
+            self.newline();
+        }
+    }
+
+    fn var(&mut self, var: &Var) {
+        self.write(var.keyword());
+        self.write(" ");
+        self.binding(&var.id);
+
+        if let Some(value) = &var.value {
+            self.space();
+            self.write("=");
+            self.space();
+            self.expression(value);
+        }
+
+        self.write(";");
+    }
+
+    fn function(&mut self, function: &Function) {
+        self.write(if function.generator { "function* " } else { "function " });
+        self.write(&function.id);
+        self.write("(");
+        self.bindings(&function.parameters);
+        self.write(")");
+        self.space();
+        self.block(&function.body);
+    }
+
+    fn if_else(&mut self, if_else: &IfElse) {
+        self.write("if");
+        self.space();
+        self.write("(");
+        self.expression(&if_else.condition);
+        self.write(")");
+        self.space();
+        self.block(&if_else.then);
+
+        if let Some(otherwise) = &if_else.otherwise {
+            self.space();
+            self.write("else");
+
+            // An `else` block that only holds another `if` is printed as an `else if` chain.
+            match otherwise.statements() {
+                [Statement::If(nested)] => {
+                    self.write(" ");
+                    self.if_else(nested);
+                },
+                _ => {
+                    self.space();
+                    self.block(otherwise);
+                },
+            }
+        }
+    }
+
+    fn while_loop(&mut self, while_: &While) {
+        if let Some(label) = &while_.label {
+            self.write(label);
+            self.write(":");
+            self.space();
+        }
+
+        self.write("while");
+        self.space();
+        self.write("(");
+        self.expression(&while_.condition);
+        self.write(")");
+        self.space();
+        self.block(&while_.then);
+    }
+
+    fn for_loop(&mut self, for_: &For) {
+        if let Some(label) = &for_.label {
+            self.write(label);
+            self.write(":");
+            self.space();
+        }
+
+        self.write("for");
+        self.space();
+        self.write("(");
+
+        match &for_.head {
+            Head::Of(binding, iterable) => {
+                // Loop bindings can be reassigned in the body, just like any other binding.
+                self.write("let ");
+                self.binding(binding);
+                self.write(" of ");
+                self.expression(iterable);
+            },
+            Head::Counted(declarations, condition, update) => {
+                self.write("let ");
+
+                for (i, (id, value)) in declarations.iter().enumerate() {
+                    if i > 0 {
+                        self.write(",");
+                        self.space();
+                    }
+
+                    self.write(id);
+                    self.space();
+                    self.write("=");
+                    self.space();
+                    self.expression(value);
+                }
+
+                self.write(";");
+                self.space();
+                self.expression(condition);
+                self.write(";");
+                self.space();
+                self.expression(update);
+            },
+        }
+
+        self.write(")");
+        self.space();
+        self.block(&for_.then);
+    }
+
+    fn jump(&mut self, keyword: &str, label: &Option<String>) {
+        self.write(keyword);
+
+        if let Some(label) = label {
+            self.write(" ");
+            self.write(label);
+        }
+
+        self.write(";");
+    }
+
+    fn block(&mut self, builder: &Builder) {
+        if builder.is_empty() {
+            self.write("{}");
+
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+
+        self.indent += 1;
+        self.statements(builder);
+        self.indent -= 1;
+
+        self.indentation();
+        self.write("}");
+    }
+
+    fn object(&mut self, members: &[(String, Expression)]) {
+        if members.is_empty() || self.compact {
+            self.write("{");
+
+            for (i, (key, value)) in members.iter().enumerate() {
+                if i > 0 {
+                    self.write(",");
+                }
+
+                self.string(key);
+                self.write(":");
+                self.operand(value, ASSIGNMENT);
+            }
+
+            self.write("}");
+
+            return;
+        }
+
+        self.write("{");
+        self.newline();
+        self.indent += 1;
+
+        for (key, value) in members {
+            self.indentation();
+            self.string(key);
+            self.write(": ");
+            self.operand(value, ASSIGNMENT);
+            self.write(",");
+            self.newline();
+        }
+
+        self.indent -= 1;
+        self.indentation();
+        self.write("}");
+    }
+
+    /// Print the target of a declaration, where objects are destructuring patterns and stay on a single line.
+    fn binding(&mut self, binding: &Expression) {
+        match binding {
+            Expression::Array(items) => {
+                self.write("[");
+                self.bindings(items);
+                self.write("]");
+            },
+            Expression::Object(members) if members.is_empty() => self.write("{}"),
+            Expression::Object(members) => {
+                self.write("{");
+                self.space();
+
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        self.write(",");
+                        self.space();
+                    }
+
+                    self.string(key);
+                    self.write(":");
+                    self.space();
+                    self.binding(value);
+                }
+
+                self.space();
+                self.write("}");
+            },
+            Expression::Prefix(op, pattern) if op == "..." => {
+                self.write(op);
+                self.binding(pattern);
+            },
+            binding => self.expression(binding),
+        }
+    }
+
+    fn bindings(&mut self, bindings: &[Expression]) {
+        for (i, binding) in bindings.iter().enumerate() {
+            if i > 0 {
+                self.write(",");
+                self.space();
+            }
+
+            self.binding(binding);
+        }
+    }
+
+    fn list(&mut self, items: &[Expression]) {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.write(",");
+                self.space();
+            }
+
+            self.operand(item, ASSIGNMENT);
+        }
+    }
+
+    /// Print an expression that needs to bind at least as tightly as `minimum`, wrapping it in parentheses if it doesn't.
+    fn operand(&mut self, expression: &Expression, minimum: u8) {
+        if precedence(expression) < minimum {
+            self.write("(");
+            self.expression(expression);
+            self.write(")");
+        } else {
+            self.expression(expression);
+        }
+    }
+
+    fn string(&mut self, s: &str) {
+        self.write("\"");
+        self.write(&escape(s, '"'));
+        self.write("\"");
+    }
+
+    fn write(&mut self, s: &str) {
+        // Source Map columns are counted in UTF-16 code units, the same as JavaScript strings.
+        for c in s.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += c.len_utf16();
+            }
+        }
+
+        self.output.push_str(s);
+    }
+
+    fn space(&mut self) {
+        if ! self.compact {
+            self.write(" ");
+        }
+    }
+
+    fn newline(&mut self) {
+        if ! self.compact {
+            self.write("\n");
+        }
+    }
+
+    fn indentation(&mut self) {
+        if ! self.compact {
+            self.write(&INDENTATION.repeat(self.indent));
+        }
+    }
+}
+
+/// Function declarations and the list of imports are separated from the surrounding statements by a blank line.
+fn separated(previous: &Statement, next: &Statement) -> bool {
+    matches!(previous, Statement::Function(_)) || matches!(next, Statement::Function(_)) ||
+        (matches!(previous, Statement::Import { .. }) && ! matches!(next, Statement::Import { .. }))
+}
+
+/// Escape the contents of a JavaScript string literal that's wrapped in `quote`, which is either `"` or a backtick
+/// for template literals, where `$` is escaped too so that it can't start an embedded expression.
+/// 
+/// Line terminators, including U+2028 and U+2029, can't appear inside of a string literal, and any other control
+/// characters are escaped so that the output stays readable.
+fn escape(s: &str, quote: char) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            c if c == quote || (c == '$' && quote == '`') => {
+                output.push('\\');
+                output.push(c);
+            },
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{b}' => output.push_str("\\v"),
+            '\u{c}' => output.push_str("\\f"),
+            '\u{2028}' | '\u{2029}' => output.push_str(&format!("\\u{:04x}", c as u32)),
+            // `\0` can't be used here, since it would be read as an octal escape when followed by a digit.
+            c if c.is_control() && (c as u32) < 0x100 => output.push_str(&format!("\\x{:02x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn precedence(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Number(n) if n.is_sign_negative() => UNARY,
+        Expression::Infix(_, op, _) => infix_precedence(op).0,
+        Expression::Prefix(..) => UNARY,
+        Expression::Index(..) | Expression::Dot(..) | Expression::OptionalIndex(..) | Expression::OptionalDot(..) | Expression::Call(..) => MEMBER,
+        Expression::Conditional(..) => CONDITIONAL,
+        Expression::Closure(..) => ASSIGNMENT,
+        _ => PRIMARY,
+    }
+}
+
+/// The precedence of a binary operator, and whether it's right-associative.
+fn infix_precedence(op: &str) -> (u8, bool) {
+    match op {
+        "**" => (14, true),
+        "*" | "/" | "%" => (13, false),
+        "+" | "-" => (12, false),
+        "<<" | ">>" | ">>>" => (11, false),
+        "<" | ">" | "<=" | ">=" | "in" | "instanceof" => (10, false),
+        "==" | "!=" | "===" | "!==" => (9, false),
+        "&" => (8, false),
+        "^" => (7, false),
+        "|" => (6, false),
+        "&&" => (5, false),
+        "||" | "??" => (4, false),
+        _ => (ASSIGNMENT, true),
+    }
+}
+
+fn repeats_sign(op: &str, operand: &Expression) -> bool {
+    match operand {
+        Expression::Prefix(inner, _) => (op == "-" || op == "+") && inner == op,
+        Expression::Number(n) => op == "-" && n.is_sign_negative(),
+        _ => false,
+    }
+}
+
+/// JavaScript doesn't allow `??` to be mixed with `&&` or `||` without parentheses, whatever their precedence.
+fn mixes_nullish(op: &str, operand: &Expression) -> bool {
+    match operand {
+        Expression::Infix(_, inner, _) => match op {
+            "??" => inner == "&&" || inner == "||",
+            "&&" | "||" => inner == "??",
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn starts_with_object(expression: &Expression) -> bool {
+    match expression {
+        Expression::Object(_) => true,
+        Expression::Index(target, _) | Expression::Dot(target, _) | Expression::OptionalIndex(target, _) | Expression::OptionalDot(target, _)
+            | Expression::Call(target, _) | Expression::Infix(target, _, _) | Expression::Conditional(target, _, _) => starts_with_object(target),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program() -> Builder {
+        let mut body = Builder::new();
+        body.return_(Some(Expression::infix(Expression::identifier("a"), "+", Expression::identifier("b"))));
+
+        let mut function = Function::new();
+        function
+            .id("add".to_owned())
+            .parameters(vec![Expression::identifier("a"), Expression::identifier("b")])
+            .body(body);
+
+        let mut then = Builder::new();
+        then.expression(Expression::from((Expression::identifier("println"), vec![Expression::from("big")])));
+
+        let mut if_else = IfElse::new(Expression::infix(Expression::identifier("total"), ">", Expression::from(2)));
+        if_else.then(then);
+
+        let mut total = Var::new();
+        total.id("total".to_owned()).as_const().value(Expression::from((Expression::identifier("add"), vec![1.into(), 2.into()])));
+
+        let mut builder = Builder::new();
+        builder
+            .import(vec!["File".to_owned()], "@std/fs".to_owned())
+            .function(function)
+            .var(total)
+            .conditional(if_else);
+
+        builder
+    }
+
+    #[test]
+    fn readable() {
+        assert_eq!(program().source(), [
+            r#"import { File } from "@std/fs";"#,
+            "",
+            "function add(a, b) {",
+            "    return a + b;",
+            "}",
+            "",
+            "const total = add(1, 2);",
+            "if (total > 2) {",
+            r#"    println("big");"#,
+            "}",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(
+            program().compact(),
+            r#"import{File}from"@std/fs";function add(a,b){return a + b;}const total=add(1,2);if(total > 2){println("big");}"#
+        );
+    }
+
+    #[test]
+    fn else_if_chains() {
+        let mut inner = IfElse::new(Expression::identifier("b"));
+        inner.otherwise(Builder::new());
+
+        let mut otherwise = Builder::new();
+        otherwise.conditional(inner);
+
+        let mut outer = IfElse::new(Expression::identifier("a"));
+        outer.otherwise(otherwise);
+
+        let mut builder = Builder::new();
+        builder.conditional(outer);
+
+        assert_eq!(builder.source(), "if (a) {} else if (b) {} else {}\n");
+    }
+
+    #[test]
+    fn conditionals() {
+        let id = Expression::identifier;
+        let conditional = Expression::conditional(id("a"), id("b"), Expression::conditional(id("c"), id("d"), id("e")));
+
+        let mut builder = Builder::new();
+        builder
+            .expression(conditional.clone())
+            .expression(Expression::infix(conditional.clone(), "+", 1.into()))
+            .expression(Expression::conditional(conditional, Expression::infix(id("x"), "=", 1.into()), id("y")));
+
+        assert_eq!(builder.source(), [
+            "a ? b : c ? d : e;",
+            "(a ? b : c ? d : e) + 1;",
+            "(a ? b : c ? d : e) ? x = 1 : y;",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn blocks() {
+        let mut body = Builder::new();
+        body.break_(Some("found".to_owned()));
+
+        let mut builder = Builder::new();
+        builder.block(Some("found".to_owned()), body).block(None, Builder::new());
+
+        assert_eq!(builder.source(), "found: {\n    break found;\n}\n{}\n");
+    }
+
+    #[test]
+    fn generators() {
+        let mut body = Builder::new();
+        body.expression(Expression::Prefix("yield".to_owned(), Box::new(1.into())));
+
+        let mut function = Function::new();
+        function.id("one".to_owned()).generator().body(body);
+
+        let mut builder = Builder::new();
+        builder.function(function);
+
+        assert_eq!(builder.source(), "function* one() {\n    yield 1;\n}\n");
+    }
+
+    #[test]
+    fn destructuring() {
+        let pattern = Expression::Array(vec![
+            Expression::identifier("a"),
+            Expression::object(vec![("name".to_owned(), Expression::identifier("name"))]),
+            Expression::Prefix("...".to_owned(), Box::new(Expression::identifier("rest"))),
+        ]);
+
+        let mut var = Var::new();
+        var.pattern(pattern.clone()).as_let().value(Expression::identifier("items"));
+
+        let mut function = Function::new();
+        function.id("first".to_owned()).parameters(vec![pattern]);
+
+        let mut builder = Builder::new();
+        builder.var(var).function(function);
+
+        assert_eq!(builder.source(), "let [a, { \"name\": name }, ...rest] = items;\n\nfunction first([a, { \"name\": name }, ...rest]) {}\n");
+        assert_eq!(builder.compact(), "let [a,{\"name\":name},...rest]=items;function first([a,{\"name\":name},...rest]){}");
+    }
+
+    #[test]
+    fn for_loops() {
+        let mut then = Builder::new();
+        then.continue_(None);
+
+        let mut of = For::of(
+            Expression::Array(vec![Expression::identifier("key"), Expression::identifier("value")]),
+            Expression::from((Expression::dot(Expression::identifier("Object"), Expression::identifier("entries")), vec![Expression::identifier("map")])),
+        );
+        of.label("outer".to_owned()).then(then);
+
+        let counted = For::counted(
+            vec![("i".to_owned(), 0.into()), ("end".to_owned(), Expression::identifier("n"))],
+            Expression::infix(Expression::identifier("i"), "<", Expression::identifier("end")),
+            Expression::infix(Expression::identifier("i"), "+=", 1.into()),
+        );
+
+        let mut builder = Builder::new();
+        builder.for_loop(of).for_loop(counted);
+
+        assert_eq!(builder.source(), "outer: for (let [key, value] of Object.entries(map)) {\n    continue;\n}\nfor (let i = 0, end = n; i < end; i += 1) {}\n");
+        assert_eq!(builder.compact(), "outer:for(let [key,value] of Object.entries(map)){continue;}for(let i=0,end=n;i < end;i += 1){}");
+    }
+
+    #[test]
+    fn object_statements() {
+        let mut builder = Builder::new();
+        builder.expression(Expression::object(vec![("foo".to_owned(), 1.into())]));
+
+        assert_eq!(builder.source(), "({\n    \"foo\": 1,\n});\n");
+    }
+
+    #[test]
+    fn source_maps() {
+        let mut body = Builder::new();
+        body.at(2, 5).return_(Some(Expression::identifier("a")));
+
+        let mut function = Function::new();
+        function
+            .id("id".to_owned())
+            .parameters(vec![Expression::identifier("a")])
+            .body(body);
+
+        let mut builder = Builder::new();
+        builder
+            .at(1, 1)
+            .function(function)
+            .at(0, 0)
+            .expression(Expression::from((Expression::identifier("id"), vec![1.into()])));
+
+        let (source, map) = builder.source_with_map();
+
+        assert_eq!(source, "function id(a) {\n    return a;\n}\n\nid(1);\n");
+        assert_eq!(map.mappings(), &[
+            Mapping { generated: (0, 0), original: (0, 0) },
+            Mapping { generated: (1, 4), original: (1, 4) },
+        ]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,157 @@
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A `Mapping` ties a position in the generated JavaScript to a position in the original source code.
+/// 
+/// Both positions are `(line, column)` pairs and are 0-based, like the positions in a Source Map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mapping {
+    pub generated: (usize, usize),
+    pub original: (usize, usize),
+}
+
+/// The `SourceMap` holds the mappings recorded by the `Printer` and can be written out as a Source Map v3 file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, mapping: Mapping) -> &mut Self {
+        self.mappings.push(mapping);
+        self
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// Find the original position of a position in the generated code.
+    /// 
+    /// This uses the closest mapping at or before the given column. When the column comes before the first mapping on its line,
+    /// the first mapping on the line is used instead, and lines without any mappings fall back to the closest line above them.
+    pub fn lookup(&self, line: usize, column: usize) -> Option<(usize, usize)> {
+        let mut on_line = self.mappings.iter().filter(|m| m.generated.0 == line);
+
+        if let Some(mapping) = on_line.clone().rfind(|m| m.generated.1 <= column).or_else(|| on_line.next()) {
+            return Some(mapping.original);
+        }
+
+        self.mappings.iter()
+            .rfind(|m| m.generated.0 < line)
+            .map(|m| m.original)
+    }
+
+    /// Encode the mappings as a Source Map v3 JSON document, where `file` is the generated file and `source` is the original one.
+    pub fn to_json(&self, file: &str, source: &str) -> String {
+        format!(
+            r#"{{"version":3,"file":{},"sources":[{}],"names":[],"mappings":"{}"}}"#,
+            json_string(file),
+            json_string(source),
+            self.encode()
+        )
+    }
+
+    fn encode(&self) -> String {
+        let mut mappings = self.mappings.clone();
+        mappings.sort_by_key(|m| m.generated);
+
+        let mut output = String::new();
+        let mut line = 0;
+        let mut previous_column = 0;
+        let mut previous_original = (0, 0);
+
+        for mapping in mappings {
+            if mapping.generated.0 > line {
+                output.push_str(&";".repeat(mapping.generated.0 - line));
+                line = mapping.generated.0;
+                previous_column = 0;
+            } else if ! output.is_empty() && ! output.ends_with(';') {
+                output.push(',');
+            }
+
+            vlq(&mut output, mapping.generated.1 as i64 - previous_column as i64);
+            vlq(&mut output, 0);
+            vlq(&mut output, mapping.original.0 as i64 - previous_original.0 as i64);
+            vlq(&mut output, mapping.original.1 as i64 - previous_original.1 as i64);
+
+            previous_column = mapping.generated.1;
+            previous_original = mapping.original;
+        }
+
+        output
+    }
+}
+
+/// Write a number as a Base64 VLQ, where the lowest bit of the first digit holds the sign.
+fn vlq(output: &mut String, value: i64) {
+    let mut value = if value < 0 { ((-value) << 1) | 1 } else { value << 1 };
+
+    loop {
+        let mut digit = value & 0b11111;
+        value >>= 5;
+
+        if value > 0 {
+            digit |= 0b100000;
+        }
+
+        output.push(BASE64[digit as usize] as char);
+
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut output = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> SourceMap {
+        let mut map = SourceMap::new();
+        map
+            .add(Mapping { generated: (0, 0), original: (0, 0) })
+            .add(Mapping { generated: (2, 4), original: (1, 4) })
+            .add(Mapping { generated: (2, 12), original: (1, 10) })
+            .add(Mapping { generated: (3, 0), original: (5, 0) });
+
+        map
+    }
+
+    #[test]
+    fn encodes_vlq_mappings() {
+        assert_eq!(
+            map().to_json("main.js", "main.tn"),
+            r#"{"version":3,"file":"main.js","sources":["main.tn"],"names":[],"mappings":"AAAA;;IACI,QAAM;AAIV"}"#
+        );
+    }
+
+    #[test]
+    fn looks_up_closest_mapping() {
+        let map = map();
+
+        assert_eq!(map.lookup(2, 13), Some((1, 10)));
+        assert_eq!(map.lookup(2, 0), Some((1, 4)));
+        assert_eq!(map.lookup(1, 0), Some((0, 0)));
+        assert_eq!(map.lookup(9, 0), Some((5, 0)));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,35 @@
+use crate::{Builder, Expression, Var, Function, IfElse, While, For, Printer};
+use std::fmt::{Display, Formatter, Result};
+
+/// A single JavaScript statement.
+/// 
+/// A `Builder` holds a list of these which can be transformed after the fact, then turned into source
+/// code with a `Printer`.
+#[derive(Debug, Clone)]
+pub enum Statement {
+    Import {
+        items: Vec<String>,
+        module: String,
+    },
+    Var(Var),
+    Function(Function),
+    If(IfElse),
+    While(While),
+    For(For),
+    /// A block statement, `{ ... }`, which can be labelled so that `break label` leaves it early.
+    Block(Option<String>, Builder),
+    Return(Option<Expression>),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Throw(Expression),
+    Expression(Expression),
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut printer = Printer::readable();
+        printer.statement(self);
+
+        write!(f, "{}", printer.finish())
+    }
+}
\ No newline at end of file
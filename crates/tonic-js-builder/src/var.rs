@@ -1,10 +1,9 @@
 use crate::Expression;
-use std::fmt::{Display, Formatter, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Var {
-    id: String,
-    value: Option<Expression>,
+    pub(crate) id: Expression,
+    pub(crate) value: Option<Expression>,
     m_const: bool,
     m_let: bool,
 }
@@ -12,7 +11,7 @@ pub struct Var {
 impl Var {
     pub fn new() -> Self {
         Self {
-            id: String::new(),
+            id: Expression::identifier(""),
             value: None,
             m_const: false,
             m_let: false,
@@ -31,7 +30,13 @@ impl Var {
     }
 
     pub fn id(&mut self, id: String) -> &mut Self {
-        self.id = id;
+        self.id = Expression::Identifier(id);
+        self
+    }
+
+    /// Declare every name in a destructuring pattern, such as `[a, ...rest]` or `{"name": name}`, instead of a single name.
+    pub fn pattern(&mut self, pattern: Expression) -> &mut Self {
+        self.id = pattern;
         self
     }
 
@@ -40,23 +45,19 @@ impl Var {
         self
     }
 
-    fn keyword(&self) -> String {
-        String::from(if self.m_let {
+    pub(crate) fn keyword(&self) -> &'static str {
+        if self.m_let {
             "let"
         } else if self.m_const {
             "const"
         } else {
             "var"
-        })
+        }
     }
 }
 
-impl Display for Var {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{} {}{};", self.keyword(), self.id, if let Some(expression) = &self.value {
-            format!(" = {}", expression)
-        } else {
-            String::from("")
-        })
+impl Default for Var {
+    fn default() -> Self {
+        Self::new()
     }
 }
\ No newline at end of file
@@ -1,28 +1,28 @@
 use crate::{Expression, Builder};
-use std::fmt::{Display, Formatter, Result};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct While {
-    condition: Expression,
-    then: Builder,
+    pub(crate) label: Option<String>,
+    pub(crate) condition: Expression,
+    pub(crate) then: Builder,
 }
 
 impl While {
     pub fn new(condition: Expression) -> Self {
         Self {
+            label: None,
             condition,
             then: Builder::new(),
         }
     }
 
-    pub fn then(&mut self, then: Builder) -> &mut Self {
-        self.then = then;
+    pub fn label(&mut self, label: String) -> &mut Self {
+        self.label = Some(label);
         self
     }
-}
 
-impl Display for While {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "while ({}) {{\n{}\n}}", self.condition, self.then)
+    pub fn then(&mut self, then: Builder) -> &mut Self {
+        self.then = then;
+        self
     }
 }
\ No newline at end of file
@@ -1,22 +1,51 @@
-use crate::TokenKind;
-use crate::{Parameter, Statement};
+use crate::{TokenKind, Span};
+use crate::{Parameter, Node, Arm};
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq)]
 pub enum Expression {
     String(String),
+    /// `"Hello, {name}!"`, where each of the parts is either a literal `String` or an embedded expression.
+    Interpolated(Vec<Expression>),
     Number(f64),
+    /// `10n`, an integer of any size, which is kept as the digits that it's written with.
+    BigInt(String),
     Bool(bool),
+    Null,
     Array(Vec<Expression>),
     Map(HashMap<String, Expression>),
     Identifier(String),
     Prefix(Op, Box<Expression>),
-    Infix(Box<Expression>, Op, Box<Expression>),
+    Infix(Box<Expression>, Op, Box<Expression>, Position),
     Call(Box<Expression>, Vec<Expression>),
-    Assign(Box<Expression>, Box<Expression>),
+    Assign(Box<Expression>, Box<Expression>, Position),
     Index(Box<Expression>, Option<Box<Expression>>),
     Dot(Box<Expression>, Box<Expression>),
-    Closure(Vec<Parameter>, Vec<Statement>),
+    /// `target?.[index]`, which is `null` when the target is, instead of an error.
+    OptionalIndex(Box<Expression>, Box<Expression>),
+    /// `target?.property`, which is `null` when the target is, instead of an error.
+    OptionalDot(Box<Expression>, Box<Expression>),
+    Closure(Vec<Parameter>, Vec<Node>),
+    /// `start..end`, the numbers from `start` up to but not including `end`, or up to and including it with `start..=end`.
+    ///
+    /// Either end can be left out, such as in `items[..5]`, and a `step` can follow the end: `0..10 step 2`.
+    Range {
+        start: Option<Box<Expression>>,
+        end: Option<Box<Expression>>,
+        inclusive: bool,
+        step: Option<Box<Expression>>,
+    },
+    /// `match subject { pattern => body, ... }`, where the first arm whose pattern matches is evaluated.
+    Match(Box<Expression>, Vec<Arm>, Position),
+    /// `if condition { a } else { b }`, which produces the value of the last expression in whichever block runs.
+    ///
+    /// An `else if` is kept as an `If` statement on its own inside of `otherwise`, the same as it is for `if` statements.
+    If {
+        condition: Box<Expression>,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+        position: Position,
+    },
 }
 
 impl Expression {
@@ -25,6 +54,28 @@ impl Expression {
     }
 }
 
+/// Where an expression is in the source code, for the expressions that can cause a diagnostic of their own. Like a
+/// `Node`, the span stops at the end of the first line, and an expression that was created by hand has a `line` of `0`.
+///
+/// Positions are metadata, so they never affect whether two expressions are equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Position {
+    pub line: usize,
+    pub span: Span,
+}
+
+impl Position {
+    pub fn new(line: usize, span: Span) -> Self {
+        Self { line, span }
+    }
+}
+
+impl PartialEq for Position {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
 /// The `Op` enumeration is used to represent prefix, infix and other operations.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Op {
@@ -47,38 +98,27 @@ pub enum Op {
     And,
     Or,
     Mod,
+    Power,
+    PowerAssign,
+    /// `??`, which gives the right-hand side when the left-hand side is `null`.
+    Coalesce,
+    CoalesceAssign,
 }
 
-impl From<TokenKind> for Op {
-    fn from(kind: TokenKind) -> Self {
-        match kind {
-            TokenKind::Plus => Op::Add,
-            TokenKind::Minus => Op::Subtract,
-            TokenKind::Asterisk => Op::Multiply,
-            TokenKind::Slash => Op::Divide,
-            TokenKind::GreaterThan => Self::GreaterThan,
-            TokenKind::LessThan => Self::LessThan,
-            TokenKind::GreaterThanEquals => Self::GreaterThanEquals,
-            TokenKind::LessThanEquals => Self::LessThanEquals,
-            TokenKind::EqualsEquals => Self::Equals,
-            TokenKind::NotEquals => Self::NotEquals,
-            TokenKind::Equals => Self::Assign,
-            TokenKind::Not => Self::Not,
-            TokenKind::And => Self::And,
-            TokenKind::Or => Self::Or,
-            TokenKind::Percent => Self::Mod,
-            TokenKind::PlusEquals => Self::AddAssign,
-            TokenKind::MinusEquals => Self::SubtractAssign,
-            TokenKind::AsteriskEquals => Self::MultiplyAssign,
-            TokenKind::SlashEquals => Self::DivideAssign,
-            _ => todo!()
-        }
+/// Only the tokens that can be used as an operator have one, so any other token is given back as the error.
+impl TryFrom<TokenKind> for Op {
+    type Error = TokenKind;
+
+    fn try_from(kind: TokenKind) -> Result<Self, Self::Error> {
+        Self::try_from(&kind)
     }
 }
 
-impl From<&TokenKind> for Op {
-    fn from(kind: &TokenKind) -> Self {
-        match kind {
+impl TryFrom<&TokenKind> for Op {
+    type Error = TokenKind;
+
+    fn try_from(kind: &TokenKind) -> Result<Self, Self::Error> {
+        Ok(match kind {
             TokenKind::Plus => Op::Add,
             TokenKind::Minus => Op::Subtract,
             TokenKind::Asterisk => Op::Multiply,
@@ -94,17 +134,26 @@ impl From<&TokenKind> for Op {
             TokenKind::And => Self::And,
             TokenKind::Or => Self::Or,
             TokenKind::Percent => Self::Mod,
+            TokenKind::DoubleAsterisk => Self::Power,
             TokenKind::PlusEquals => Self::AddAssign,
             TokenKind::MinusEquals => Self::SubtractAssign,
             TokenKind::AsteriskEquals => Self::MultiplyAssign,
             TokenKind::SlashEquals => Self::DivideAssign,
-            _ => todo!()
-        }
+            TokenKind::DoubleAsteriskEquals => Self::PowerAssign,
+            TokenKind::DoubleQuestion => Self::Coalesce,
+            TokenKind::DoubleQuestionEquals => Self::CoalesceAssign,
+            _ => return Err(kind.clone()),
+        })
     }
 }
 
 impl Op {
     pub fn math(&self) -> bool {
-        matches!(self, Self::Add | Self::Subtract | Self::Multiply | Self::Divide)
+        matches!(self, Self::Add | Self::Subtract | Self::Multiply | Self::Divide | Self::Power)
+    }
+
+    /// Whether the operator assigns its result back to the left-hand side, such as `+=`.
+    pub fn assigns(&self) -> bool {
+        matches!(self, Self::AddAssign | Self::SubtractAssign | Self::MultiplyAssign | Self::DivideAssign | Self::PowerAssign | Self::CoalesceAssign)
     }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use std::iter::Peekable;
 use std::str::Chars;
-use crate::{Token, TokenKind, Span};
+use crate::{Token, TokenKind, Fragment, Span, LexError};
 
 /// The main `Lexer` that handles producing `Token` instances.
 /// 
@@ -11,6 +11,10 @@ pub struct Lexer<'l> {
     line: usize,
     column: usize,
     current: char,
+    /// Whether the last token was `break` or `continue`, which can be followed by a label.
+    jump: bool,
+    /// The line of the last token, and whether it was a brace, since a label that names a loop has to start a statement.
+    last: Option<(usize, bool)>,
 }
 
 impl<'l> Lexer<'l> {
@@ -25,6 +29,8 @@ impl<'l> Lexer<'l> {
             column: 0,
             line: 1,
             current: '\0',
+            jump: false,
+            last: None,
         };
 
         this.read();
@@ -36,16 +42,24 @@ impl<'l> Lexer<'l> {
     /// Also increments line and column counters to keep track of location.
     fn read(&mut self) -> Option<char> {
         if let Some(c) = self.source.next() {
-            self.current = c;
-            self.column += 1;
-
-            if c == '\n' {
+            // The line counter moves on once we step past the `\n`, so that tokens at the end of a
+            // line still end on the same line that they started on.
+            if self.current == '\n' {
                 self.line += 1;
                 self.column = 0;
             }
 
+            self.current = c;
+            self.column += 1;
+
             Some(self.current)
         } else {
+            // Step past the final character once so that the span of the last token in the source
+            // ends after it, just like every other token.
+            if self.current != '\0' {
+                self.column += 1;
+            }
+
             self.current = '\0';
 
             None
@@ -59,6 +73,78 @@ impl<'l> Lexer<'l> {
         }
     }
 
+    /// Skip over whitespace, `//` line comments and `/* */` block comments.
+    /// 
+    /// Doc comments (`///`) are left alone so that they can be produced as tokens. If a block comment
+    /// is never closed, an error token is returned instead.
+    fn skip_trivia(&mut self) -> Option<Token> {
+        loop {
+            self.skip_whitespace();
+
+            match (self.current, self.source.peek().copied()) {
+                ('/', Some('/')) if ! self.at_doc_comment() => self.skip_line_comment(),
+                ('/', Some('*')) => if let Some(error) = self.skip_block_comment() {
+                    return Some(error)
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    fn skip_line_comment(&mut self) {
+        while self.current != '\n' && self.read().is_some() {}
+    }
+
+    /// Block comments can be nested, so we need to keep track of how deep we are.
+    fn skip_block_comment(&mut self) -> Option<Token> {
+        let position = self.pos();
+        let mut depth = 0;
+
+        loop {
+            match (self.current, self.source.peek().copied()) {
+                ('/', Some('*')) => {
+                    self.read();
+                    depth += 1;
+                },
+                ('*', Some('/')) => {
+                    self.read();
+                    depth -= 1;
+                },
+                ('\0', None) => return Some(Token::new(TokenKind::Error(LexError::UnterminatedComment), position.0, (position.1, position.1 + 2))),
+                _ => (),
+            }
+
+            self.read();
+
+            if depth == 0 {
+                return None
+            }
+        }
+    }
+
+    /// Check whether the current character starts a `///` doc comment. Much like Rust, four or more slashes
+    /// is treated as a regular comment.
+    fn at_doc_comment(&self) -> bool {
+        let mut ahead = self.source.clone();
+
+        self.current == '/' && ahead.next() == Some('/') && ahead.next() == Some('/') && ahead.next() != Some('/')
+    }
+
+    fn parse_doc_comment(&mut self) -> Token {
+        let position = self.pos();
+        let mut buffer = String::new();
+
+        // Skip over the `///` characters.
+        self.read();
+        self.read();
+
+        while self.read().is_some() && self.current != '\n' {
+            buffer.push(self.current);
+        }
+
+        Token::new(TokenKind::DocComment(buffer), position.0, (position.1, self.column))
+    }
+
     fn parse_identifier_or_keyword(&mut self) -> Token {
         let position = self.pos();
         let mut buffer = String::from(self.current);
@@ -84,95 +170,408 @@ impl<'l> Lexer<'l> {
 
     fn parse_symbol(&mut self) -> Token {
         let position = self.pos();
-        let buffer = String::from(self.current);
+        let mut buffer = String::from(self.current);
 
         self.read();
 
-        if ! is_valid_symbol_char(self.current) {
-            return Token::new(symbol(&buffer).unwrap(), position.0, (position.1, self.column))
-        }
+        // Symbols are matched greedily, so `**=` is lexed as a single token rather than `**` followed by `=`.
+        loop {
+            let mut longer = buffer.clone();
+            longer.push(self.current);
 
-        let mut multi = String::from(&buffer);
-        multi.push(self.current);
+            if symbol(&longer).is_none() {
+                break;
+            }
 
-        if symbol(&multi).is_some() {
+            buffer = longer;
             self.read();
-
-            return Token::new(symbol(&multi).unwrap(), position.0, (position.1, self.column))
         }
 
-        Token::new(symbol(&buffer).unwrap(), position.0, (position.1, self.column))
+        // Some characters, such as `&` and `|`, are only valid as part of a longer symbol.
+        match symbol(&buffer) {
+            Some(kind) => Token::new(kind, position.0, (position.1, self.column)),
+            None => Token::new(TokenKind::Error(LexError::UnexpectedCharacter(buffer.chars().next().unwrap())), position.0, (position.1, self.column)),
+        }
     }
 
+    /// Parse a number, which can be written in hex (`0xff`), octal (`0o17`) or binary (`0b1010`), have an exponent
+    /// (`1e-9`), and use `_` to separate its digits. A trailing `n` makes it a `BigInt` instead.
     fn parse_numeric(&mut self) -> Token {
         let position = self.pos();
         let mut buffer = String::from(self.current);
 
-        loop {
-            if self.read().is_none() {
-                break;
-            }
+        while self.read().is_some() {
+            let c = self.current;
+            // The sign of an exponent, which hex numbers can't have since `e` is one of their digits.
+            let sign = (c == '+' || c == '-') && buffer.ends_with(['e', 'E']) && radix(&buffer).0 == 10
+                && self.source.peek().is_some_and(char::is_ascii_digit);
 
-            if ! self.current.is_numeric() && self.current != '.' {
+            // A `.` is only part of the number when a digit follows it, otherwise it's the start of
+            // another token. Letters are always read, so that something like `10px` is reported as a
+            // malformed number instead of being split into two tokens.
+            if c.is_ascii_alphanumeric() || c == '_' || sign || (c == '.' && self.source.peek().is_some_and(char::is_ascii_digit)) {
+                buffer.push(c);
+            } else {
                 break;
             }
+        }
+
+        match number(&buffer) {
+            Some(kind) => Token::new(kind, position.0, (position.1, self.column)),
+            None => Token::new(TokenKind::Error(LexError::MalformedNumber(buffer)), position.0, (position.1, self.column)),
+        }
+    }
+
+    /// Parse a string, where `{expression}` embeds the tokens of an expression. If a string has any of these, it's
+    /// produced as an `Interpolated` token instead of a plain `String`.
+    ///
+    /// Strings can be wrapped in `"` or `'`, or in `"""` to span multiple lines. When the opening `"""` ends its line,
+    /// that line break is dropped, along with the indentation that every line shares and the line that the closing `"""`
+    /// is on, if there's nothing else on it.
+    fn parse_string(&mut self) -> Token {
+        let position = self.pos();
+        let quote = self.current;
+        let indentation = if self.at_triple_quote() { Some(self.triple_quote_indentation()) } else { None };
+
+        let mut buffer = String::new();
+        let mut fragments = Vec::new();
+        let mut error = None;
+        // Whether the current line of a `"""` string has only had indentation on it so far.
+        let mut blank = false;
+
+        // Skip over the opening quote, or all three of them.
+        for _ in 0..if indentation.is_some() { 3 } else { 1 } {
+            self.read();
+        }
+
+        if let (Some(indentation), '\n') = (indentation, self.current) {
+            self.read();
+            self.skip_indentation(indentation);
+
+            blank = true;
+        }
+
+        loop {
+            match self.current {
+                '\0' if self.source.peek().is_none() => {
+                    return Token::new(TokenKind::Error(LexError::UnterminatedString), position.0, (position.1, position.1 + 1))
+                },
+                c if c == quote && (indentation.is_none() || self.at_triple_quote()) => {
+                    // Skip over the closing quote, or all three of them.
+                    for _ in 0..if indentation.is_some() { 3 } else { 1 } {
+                        self.read();
+                    }
+
+                    if blank {
+                        if let Some(end) = buffer.rfind('\n') {
+                            buffer.truncate(end);
+                        }
+                    }
+
+                    break;
+                },
+                '\n' if indentation.is_some() => {
+                    buffer.push('\n');
+
+                    self.read();
+                    self.skip_indentation(indentation.unwrap_or_default());
+
+                    blank = true;
+                },
+                '\\' => {
+                    let escape = self.pos();
+
+                    blank = false;
+
+                    if self.read().is_none() {
+                        continue;
+                    }
 
-            if self.current == '.' && buffer.contains('.') {
-                panic!("Cannot have more than 1 `.` in a numeric value.");
+                    match self.parse_escape() {
+                        Ok(c) => buffer.push(c),
+                        // Keep going until the end of the string so that the rest of it isn't lexed as code,
+                        // but only report the first invalid escape sequence.
+                        Err(lex_error) => if error.is_none() {
+                            error = Some(Token::new(TokenKind::Error(lex_error), escape.0, (escape.1, self.column + 1)));
+                        },
+                    }
+
+                    self.read();
+                },
+                '{' => {
+                    let tokens = match self.parse_interpolation() {
+                        Ok(tokens) => tokens,
+                        Err(error) => return error,
+                    };
+
+                    if ! buffer.is_empty() {
+                        fragments.push(Fragment::Literal(std::mem::take(&mut buffer)));
+                    }
+
+                    fragments.push(Fragment::Code(tokens));
+
+                    blank = false;
+                },
+                '}' => {
+                    if error.is_none() {
+                        error = Some(Token::new(TokenKind::Error(LexError::UnmatchedBrace), self.line, (self.column, self.column + 1)));
+                    }
+
+                    self.read();
+                },
+                c => {
+                    buffer.push(c);
+                    self.read();
+
+                    blank = blank && (c == ' ' || c == '\t');
+                },
             }
+        }
+
+        if let Some(error) = error {
+            return error
+        }
+
+        if fragments.is_empty() {
+            return Token::new(TokenKind::String(buffer), position.0, (position.1, self.column))
+        }
+
+        if ! buffer.is_empty() {
+            fragments.push(Fragment::Literal(buffer));
+        }
+
+        Token::new(TokenKind::Interpolated(fragments), position.0, (position.1, self.column))
+    }
+
+    /// Read the rest of an escape sequence, once the lexer has stepped past its `\`. The lexer is left on the last
+    /// character of the sequence.
+    fn parse_escape(&mut self) -> Result<char, LexError> {
+        match self.current {
+            // `\x7f`, which is always two hex digits.
+            'x' => {
+                let digits = self.hex_digits(2);
+
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) if digits.len() == 2 => Ok(char::from(byte)),
+                    _ => Err(LexError::MalformedEscape(format!("\\x{}", digits))),
+                }
+            },
+            // `\u{1F600}`, which is one to six hex digits that make up a Unicode scalar value.
+            'u' => {
+                if self.source.peek() != Some(&'{') {
+                    return Err(LexError::MalformedEscape("\\u".to_owned()))
+                }
 
-            if self.current == '.' {
                 self.read();
-                buffer.push('.');
-            }
 
-            buffer.push(self.current);
+                let digits = self.hex_digits(6);
+
+                if self.source.peek() != Some(&'}') {
+                    return Err(LexError::MalformedEscape(format!("\\u{{{}", digits)))
+                }
+
+                self.read();
+
+                u32::from_str_radix(&digits, 16).ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| LexError::MalformedEscape(format!("\\u{{{}}}", digits)))
+            },
+            c => unescape(c).ok_or(LexError::InvalidEscape(c)),
+        }
+    }
+
+    /// Read up to `limit` hex digits that follow the current character.
+    fn hex_digits(&mut self, limit: usize) -> String {
+        let mut digits = String::new();
+
+        while digits.len() < limit && self.source.peek().is_some_and(char::is_ascii_hexdigit) {
+            self.read();
+            digits.push(self.current);
         }
 
-        // We can safely unwrap the value here as we escape from the loop early 
-        // when a non-numeric value is encountered.
-        Token::new(TokenKind::Number(buffer.parse().unwrap()), position.0, (position.1, self.column))
+        digits
     }
 
-    fn parse_string(&mut self) -> Token {
+    /// Parse a raw string, `r"..."`, where backslashes and braces are just characters. The quotes can be wrapped in
+    /// any number of `#`s, such as `r#"..."#`, so that the string can contain a `"` that isn't followed by as many.
+    fn parse_raw_string(&mut self) -> Token {
         let position = self.pos();
         let mut buffer = String::new();
-        let mut escaping = false;
+        let mut hashes = 0;
+
+        // Skip over the `r` character.
+        self.read();
+
+        while self.current == '#' {
+            hashes += 1;
+            self.read();
+        }
+
+        if self.current != '"' {
+            return Token::new(TokenKind::Error(LexError::UnexpectedCharacter('#')), position.0, (position.1 + 1, position.1 + 2))
+        }
 
         loop {
             if self.read().is_none() {
+                return Token::new(TokenKind::Error(LexError::UnterminatedString), position.0, (position.1, position.1 + 1))
+            }
+
+            let mut ahead = self.source.clone();
+
+            if self.current == '"' && (0..hashes).all(|_| ahead.next() == Some('#')) {
+                // Skip over the closing `"` and its `#`s.
+                for _ in 0..=hashes {
+                    self.read();
+                }
+
                 break;
             }
 
-            if escaping {
-                buffer.push(match self.current {
-                    't' => '\t',
-                    'n' => '\n',
-                    'r' => '\r',
-                    _ => self.current
-                });
+            buffer.push(self.current);
+        }
+
+        Token::new(TokenKind::String(buffer), position.0, (position.1, self.column))
+    }
+
+    fn at_triple_quote(&self) -> bool {
+        let mut ahead = self.source.clone();
+
+        self.current == '"' && ahead.next() == Some('"') && ahead.next() == Some('"')
+    }
 
-                escaping = false;
-                continue;
+    /// Work out how much indentation the lines of a `"""` string share, by looking ahead to the closing `"""`. Blank
+    /// lines don't count, but the line that the closing `"""` is on does.
+    fn triple_quote_indentation(&self) -> usize {
+        let mut ahead = self.source.clone().skip(2).peekable();
+
+        // Without a line break after the opening `"""`, the first line isn't indented, so nothing is stripped.
+        if ahead.next() != Some('\n') {
+            return 0
+        }
+
+        let mut minimum = usize::MAX;
+        let mut width = 0;
+        let mut indented = true;
+
+        while let Some(c) = ahead.next() {
+            match c {
+                ' ' | '\t' if indented => width += 1,
+                '\n' => {
+                    width = 0;
+                    indented = true;
+                },
+                _ => {
+                    if indented {
+                        minimum = minimum.min(width);
+                        indented = false;
+                    }
+
+                    let mut rest = ahead.clone();
+
+                    if c == '\\' {
+                        ahead.next();
+                    } else if c == '"' && rest.next() == Some('"') && rest.next() == Some('"') {
+                        break;
+                    }
+                },
             }
+        }
 
-            if is_valid_string_wrapper(self.current) {
-                // Skip over the `"` character.
-                self.read();
+        if minimum == usize::MAX { 0 } else { minimum }
+    }
 
+    /// Skip over up to `width` spaces or tabs at the start of a line.
+    fn skip_indentation(&mut self, width: usize) {
+        for _ in 0..width {
+            if self.current != ' ' && self.current != '\t' {
                 break;
             }
 
-            if self.current == '\\' {
-                escaping = true;
+            self.read();
+        }
+    }
+
+    /// Check whether the `'` at the current position starts a label, rather than a single-quoted string. A label either
+    /// follows `break` or `continue`, or it starts a statement and is followed by a `:` and the loop that it names, such
+    /// as `'outer: while`. Anywhere else, `'note: for details'` is just a string.
+    fn at_label(&self) -> bool {
+        let mut ahead = self.source.clone().peekable();
+        let mut name = 0;
+
+        while ahead.next_if(|c| is_valid_identifier_char(*c)).is_some() {
+            name += 1;
+        }
+
+        if name == 0 {
+            return false
+        }
+
+        if self.jump {
+            return true
+        }
+
+        let starts_statement = self.last.is_none_or(|(line, brace)| brace || line != self.line);
+
+        if ! starts_statement || ahead.next() != Some(':') {
+            return false
+        }
+
+        while ahead.next_if(char::is_ascii_whitespace).is_some() {}
+
+        let keyword = std::iter::from_fn(|| ahead.next_if(|c| is_valid_identifier_char(*c))).collect::<String>();
+
+        matches!(keyword.as_str(), "while" | "for")
+    }
+
+    /// Lex the tokens between a `{` inside of a string and the `}` that closes it, leaving the lexer just after the `}`.
+    /// Both braces have to be on the same line.
+    fn parse_interpolation(&mut self) -> Result<Vec<Token>, Token> {
+        let open = self.pos();
+        let unclosed = || Token::new(TokenKind::Error(LexError::UnclosedInterpolation), open.0, (open.1, open.1 + 1));
+
+        let mut tokens = Vec::new();
+        let mut depth = 0;
 
-                continue;
+        // Skip over the `{` character.
+        self.read();
+
+        loop {
+            // Without a `}`, the `"` that was meant to close the string starts a new one, so an embedded expression has
+            // to be closed on the same line that it was opened on.
+            let Some(token) = self.next() else { return Err(unclosed()) };
+
+            if self.line != open.0 {
+                return Err(unclosed())
+            }
+
+            match token.kind {
+                TokenKind::Error(LexError::UnterminatedString) => return Err(unclosed()),
+                TokenKind::Error(_) => return Err(token),
+                TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightBrace if depth == 0 => break,
+                TokenKind::RightBrace => depth -= 1,
+                _ => {},
             }
 
-            buffer.push(self.current)
+            tokens.push(token);
         }
 
-        Token::new(TokenKind::String(buffer), position.0, (position.1, self.column))
+        if tokens.is_empty() {
+            return Err(Token::new(TokenKind::Error(LexError::EmptyInterpolation), open.0, (open.1, self.column)))
+        }
+
+        Ok(tokens)
+    }
+
+    fn parse_label(&mut self) -> Token {
+        let position = self.pos();
+        let mut buffer = String::new();
+
+        while self.read().is_some() && is_valid_identifier_char(self.current) {
+            buffer.push(self.current);
+        }
+
+        Token::new(TokenKind::Label(buffer), position.0, (position.1, self.column))
     }
 
     fn pos(&self) -> Span {
@@ -184,24 +583,118 @@ impl<'l> Iterator for Lexer<'l> {
     type Item = Token;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace();
+        if let Some(error) = self.skip_trivia() {
+            return Some(error)
+        }
 
-        Some(match self.current {
+        let token = match self.current {
+            '\0' if self.source.peek().is_none() => return None,
+            _ if self.at_doc_comment() => self.parse_doc_comment(),
+            'r' if matches!(self.source.peek(), Some('"' | '#')) => self.parse_raw_string(),
             _ if is_valid_identifier_char(self.current) => self.parse_identifier_or_keyword(),
             _ if is_valid_symbol_char(self.current) => self.parse_symbol(),
-            _ if self.current.is_numeric() => self.parse_numeric(),
+            _ if self.current.is_ascii_digit() => self.parse_numeric(),
+            '\'' if self.at_label() => self.parse_label(),
             _ if is_valid_string_wrapper(self.current) => self.parse_string(),
-            _ => return None
-        })
+            c => {
+                let position = self.pos();
+
+                self.read();
+
+                Token::new(TokenKind::Error(LexError::UnexpectedCharacter(c)), position.0, (position.1, self.column))
+            },
+        };
+
+        self.jump = matches!(token.kind, TokenKind::Break | TokenKind::Continue);
+        self.last = Some((token.line, matches!(token.kind, TokenKind::LeftBrace | TokenKind::RightBrace)));
+
+        Some(token)
+    }
+}
+
+fn unescape(c: char) -> Option<char> {
+    Some(match c {
+        't' => '\t',
+        'n' => '\n',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' => '\\',
+        '"' => '"',
+        '\'' => '\'',
+        '{' => '{',
+        '}' => '}',
+        _ => return None
+    })
+}
+
+/// Convert the source of a number into a `Number` or `BigInt` token, or `None` if it's malformed.
+fn number(source: &str) -> Option<TokenKind> {
+    let (big, source) = match source.strip_suffix('n') {
+        Some(source) => (true, source),
+        None => (false, source),
+    };
+
+    let (radix, digits) = radix(source);
+
+    // A `_` has to sit between two digits, so `1_000` is fine but `1__000`, `1_` and `1_.5` aren't.
+    let separated = digits.char_indices().filter(|(_, c)| *c == '_').all(|(i, _)| {
+        let before = digits[..i].chars().next_back();
+        let after = digits[i + 1..].chars().next();
+
+        [before, after].iter().all(|c| c.is_some_and(|c| c.is_digit(radix)))
+    });
+
+    let digits = digits.replace('_', "");
+
+    if ! separated || digits.is_empty() {
+        return None
+    }
+
+    if big {
+        if ! digits.chars().all(|c| c.is_digit(radix)) {
+            return None
+        }
+
+        // JavaScript doesn't allow leading zeros in a decimal `BigInt`.
+        return Some(TokenKind::BigInt(match radix {
+            10 => match digits.trim_start_matches('0') {
+                "" => "0".to_owned(),
+                digits => digits.to_owned(),
+            },
+            _ => format!("{}{}", &source[..2], digits),
+        }))
+    }
+
+    let n = if radix != 10 {
+        digits.chars().try_fold(0.0, |n, c| Some(n * radix as f64 + c.to_digit(radix)? as f64))
+    } else if digits.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) {
+        // Rust's float parsing accepts a few things that Tonic doesn't, such as `inf`, so only digits, a `.`
+        // and an exponent are let through.
+        digits.parse().ok()
+    } else {
+        None
+    };
+
+    // A number too big for a float, such as `1e999`, would otherwise become infinity.
+    n.filter(|n: &f64| n.is_finite()).map(TokenKind::Number)
+}
+
+/// Split the prefix off of a number, returning its radix and the rest of its digits.
+fn radix(source: &str) -> (u32, &str) {
+    match source.get(..2) {
+        Some("0x") => (16, &source[2..]),
+        Some("0o") => (8, &source[2..]),
+        Some("0b") => (2, &source[2..]),
+        _ => (10, source),
     }
 }
 
 fn is_valid_string_wrapper(c: char) -> bool {
-    c == '"'
+    c == '"' || c == '\''
 }
 
 fn is_valid_symbol_char(c: char) -> bool {
-    ['+', '-', '*', '/', '%', '{', '}', '(', ')', '[', ']', ':', ';', ',', '=', '!', '>', '<', '.', '&', '|'].contains(&c)
+    ['+', '-', '*', '/', '%', '{', '}', '(', ')', '[', ']', ':', ';', ',', '=', '!', '>', '<', '.', '&', '|', '?'].contains(&c)
 }
 
 fn is_valid_identifier_char(c: char) -> bool {
@@ -234,14 +727,24 @@ fn symbol(s: &str) -> Option<TokenKind> {
         "<" => TokenKind::LessThan,
         "<=" => TokenKind::LessThanEquals,
         "." => TokenKind::Dot,
+        ".." => TokenKind::DoubleDot,
+        "..=" => TokenKind::DoubleDotEquals,
+        "..." => TokenKind::Ellipsis,
         "!" => TokenKind::Not,
         "&&" => TokenKind::And,
         "||" => TokenKind::Or,
         "->" => TokenKind::Arrow,
+        "=>" => TokenKind::FatArrow,
+        "|" => TokenKind::Pipe,
+        "?" => TokenKind::Question,
+        "?." => TokenKind::QuestionDot,
+        "??" => TokenKind::DoubleQuestion,
+        "??=" => TokenKind::DoubleQuestionEquals,
         "+=" => TokenKind::PlusEquals,
         "-=" => TokenKind::MinusEquals,
         "*=" => TokenKind::AsteriskEquals,
         "/=" => TokenKind::SlashEquals,
+        "**=" => TokenKind::DoubleAsteriskEquals,
         _ => return None
     })
 }
@@ -250,16 +753,22 @@ fn keyword(s: &str) -> Option<TokenKind> {
     Some(match s {
         "fn" => TokenKind::Fn,
         "let" => TokenKind::Let,
+        "const" => TokenKind::Const,
         "if" => TokenKind::If,
         "else" => TokenKind::Else,
         "while" => TokenKind::While,
+        "for" => TokenKind::For,
+        "in" => TokenKind::In,
         "return" => TokenKind::Return,
         "break" => TokenKind::Break,
         "continue" => TokenKind::Continue,
         "true" => TokenKind::True,
         "false" => TokenKind::False,
+        "null" => TokenKind::Null,
         "use" => TokenKind::Use,
         "from" => TokenKind::From,
+        "type" => TokenKind::Type,
+        "match" => TokenKind::Match,
         _ => return None
     })
 }
@@ -270,25 +779,31 @@ mod tests {
 
     #[test]
     fn keywords() {
-        matches("fn if else while return break continue let true false use from", vec![
+        matches("fn if else while for in return break continue let const true false null use from type match", vec![
             TokenKind::Fn,
             TokenKind::If,
             TokenKind::Else,
             TokenKind::While,
+            TokenKind::For,
+            TokenKind::In,
             TokenKind::Return,
             TokenKind::Break,
             TokenKind::Continue,
             TokenKind::Let,
+            TokenKind::Const,
             TokenKind::True,
             TokenKind::False,
+            TokenKind::Null,
             TokenKind::Use,
             TokenKind::From,
+            TokenKind::Type,
+            TokenKind::Match,
         ]);
     }
 
     #[test]
     fn symbols() {
-        matches("+ - * / % ** ( ) { } [ ] : :: ; , = == != > < >= <= . ! -> += -= *= /=", vec![
+        matches("+ - * / % ** ( ) { } [ ] : :: ; , = == != > < >= <= . .. ..= ... ! -> => += -= *= /= **= | ? ?. ?? ??=", vec![
             TokenKind::Plus,
             TokenKind::Minus,
             TokenKind::Asterisk,
@@ -313,12 +828,34 @@ mod tests {
             TokenKind::GreaterThanEquals,
             TokenKind::LessThanEquals,
             TokenKind::Dot,
+            TokenKind::DoubleDot,
+            TokenKind::DoubleDotEquals,
+            TokenKind::Ellipsis,
             TokenKind::Not,
             TokenKind::Arrow,
+            TokenKind::FatArrow,
             TokenKind::PlusEquals,
             TokenKind::MinusEquals,
             TokenKind::AsteriskEquals,
             TokenKind::SlashEquals,
+            TokenKind::DoubleAsteriskEquals,
+            TokenKind::Pipe,
+            TokenKind::Question,
+            TokenKind::QuestionDot,
+            TokenKind::DoubleQuestion,
+            TokenKind::DoubleQuestionEquals,
+        ]);
+    }
+
+    #[test]
+    fn ranges() {
+        matches("0..10 1.5..2", vec![
+            TokenKind::Number(0.0),
+            TokenKind::DoubleDot,
+            TokenKind::Number(10.0),
+            TokenKind::Number(1.5),
+            TokenKind::DoubleDot,
+            TokenKind::Number(2.0),
         ]);
     }
 
@@ -329,6 +866,77 @@ mod tests {
             TokenKind::Number(12345.6789),
             TokenKind::Number(9876.0),
         ]);
+
+        matches("0xFF 0o17 0b1010 1_000_000 0xdead_beef 1e-9 2.5E3 1e+2 0.1_5", vec![
+            TokenKind::Number(255.0),
+            TokenKind::Number(15.0),
+            TokenKind::Number(10.0),
+            TokenKind::Number(1000000.0),
+            TokenKind::Number(3735928559.0),
+            TokenKind::Number(1e-9),
+            TokenKind::Number(2500.0),
+            TokenKind::Number(100.0),
+            TokenKind::Number(0.15),
+        ]);
+
+        matches("10n 0n 007n 0xFFn 123_456_789_012_345_678_901n 1e3-1", vec![
+            TokenKind::BigInt("10".into()),
+            TokenKind::BigInt("0".into()),
+            TokenKind::BigInt("7".into()),
+            TokenKind::BigInt("0xFF".into()),
+            TokenKind::BigInt("123456789012345678901".into()),
+            TokenKind::Number(1000.0),
+            TokenKind::Minus,
+            TokenKind::Number(1.0),
+        ]);
+    }
+
+    #[test]
+    fn malformed_numbers() {
+        matches("0x 0b102 0o8 1__0 1_ 1_.5 0x_1 1e 10px 1.5n 1e3n 1e999 ٣", vec![
+            TokenKind::Error(LexError::MalformedNumber("0x".into())),
+            TokenKind::Error(LexError::MalformedNumber("0b102".into())),
+            TokenKind::Error(LexError::MalformedNumber("0o8".into())),
+            TokenKind::Error(LexError::MalformedNumber("1__0".into())),
+            TokenKind::Error(LexError::MalformedNumber("1_".into())),
+            TokenKind::Error(LexError::MalformedNumber("1_.5".into())),
+            TokenKind::Error(LexError::MalformedNumber("0x_1".into())),
+            TokenKind::Error(LexError::MalformedNumber("1e".into())),
+            TokenKind::Error(LexError::MalformedNumber("10px".into())),
+            TokenKind::Error(LexError::MalformedNumber("1.5n".into())),
+            TokenKind::Error(LexError::MalformedNumber("1e3n".into())),
+            TokenKind::Error(LexError::MalformedNumber("1e999".into())),
+            TokenKind::Error(LexError::UnexpectedCharacter('٣')),
+        ]);
+    }
+
+    #[test]
+    fn comments() {
+        matches("1 // one\n2 /* two */ 3 /* /* nested */ comment */ 4 //// not a doc comment", vec![
+            TokenKind::Number(1.0),
+            TokenKind::Number(2.0),
+            TokenKind::Number(3.0),
+            TokenKind::Number(4.0),
+        ]);
+
+        matches("/// Adds two numbers.\nfn add", vec![
+            TokenKind::DocComment(" Adds two numbers.".into()),
+            TokenKind::Fn,
+            TokenKind::Identifier("add".into()),
+        ]);
+
+        matches("1 / 2", vec![
+            TokenKind::Number(1.0),
+            TokenKind::Slash,
+            TokenKind::Number(2.0),
+        ]);
+    }
+
+    #[test]
+    fn doc_comment_spans() {
+        let token = Lexer::new("let a = 1\n    /// Docs").nth(4).unwrap();
+
+        assert_eq!((token.line, token.span), (2, (5, 13)));
     }
 
     #[test]
@@ -340,13 +948,185 @@ mod tests {
         ]);
     }
 
-    fn matches(source: &str, expected: Vec<TokenKind>) {
-        let mut lexer = Lexer::new(source); 
-        let mut kinds = Vec::new();
+    #[test]
+    fn raw_strings() {
+        matches(r###"r"C:\Users\{name}" r#"say "hi""# r##"a "# b"## r"two
+lines" r"###, vec![
+            TokenKind::String(r"C:\Users\{name}".into()),
+            TokenKind::String(r#"say "hi""#.into()),
+            TokenKind::String(r##"a "# b"##.into()),
+            TokenKind::String("two\nlines".into()),
+            TokenKind::Identifier("r".into()),
+        ]);
+    }
 
-        while let Some(t) = lexer.next() {
-            kinds.push(t.kind);
-        }
+    #[test]
+    fn triple_quoted_strings() {
+        matches("\"\"\"\n    SELECT *\n      FROM users\n\n    WHERE id = {id}\n    \"\"\"", vec![
+            TokenKind::Interpolated(vec![
+                Fragment::Literal("SELECT *\n  FROM users\n\nWHERE id = ".into()),
+                Fragment::Code(vec![Token::new(TokenKind::Identifier("id".into()), 5, (17, 19))]),
+            ]),
+        ]);
+
+        matches("\"\"\"\n  a \"quoted\" \"\" b\n  c\"\"\" \"\"\"one line\"\"\"", vec![
+            TokenKind::String("a \"quoted\" \"\" b\nc".into()),
+            TokenKind::String("one line".into()),
+        ]);
+    }
+
+    #[test]
+    fn single_quoted_strings() {
+        matches(r#"'hello' 'say "hi"' 'it\'s' '{a}!' 'a'"#, vec![
+            TokenKind::String("hello".into()),
+            TokenKind::String("say \"hi\"".into()),
+            TokenKind::String("it's".into()),
+            TokenKind::Interpolated(vec![
+                Fragment::Code(vec![Token::new(TokenKind::Identifier("a".into()), 1, (30, 31))]),
+                Fragment::Literal("!".into()),
+            ]),
+            TokenKind::String("a".into()),
+        ]);
+
+        matches("'outer: while true { continue 'outer }", vec![
+            TokenKind::Label("outer".into()),
+            TokenKind::Colon,
+            TokenKind::While,
+            TokenKind::True,
+            TokenKind::LeftBrace,
+            TokenKind::Continue,
+            TokenKind::Label("outer".into()),
+            TokenKind::RightBrace,
+        ]);
+
+        matches("println('note: for details')\n'outer: for x in xs {}", vec![
+            TokenKind::Identifier("println".into()),
+            TokenKind::LeftParen,
+            TokenKind::String("note: for details".into()),
+            TokenKind::RightParen,
+            TokenKind::Label("outer".into()),
+            TokenKind::Colon,
+            TokenKind::For,
+            TokenKind::Identifier("x".into()),
+            TokenKind::In,
+            TokenKind::Identifier("xs".into()),
+            TokenKind::LeftBrace,
+            TokenKind::RightBrace,
+        ]);
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        matches(r#""\x41\x7e" "\u{1F600} \u{e9}" "\u{0}""#, vec![
+            TokenKind::String("A~".into()),
+            TokenKind::String("\u{1F600} \u{e9}".into()),
+            TokenKind::String("\0".into()),
+        ]);
+
+        matches(r#""\x4" "\xzz" "\u41" "\u{110000}" "\u{1234567}""#, vec![
+            TokenKind::Error(LexError::MalformedEscape("\\x4".into())),
+            TokenKind::Error(LexError::MalformedEscape("\\x".into())),
+            TokenKind::Error(LexError::MalformedEscape("\\u".into())),
+            TokenKind::Error(LexError::MalformedEscape("\\u{110000}".into())),
+            TokenKind::Error(LexError::MalformedEscape("\\u{123456".into())),
+        ]);
+    }
+
+    #[test]
+    fn interpolated_strings() {
+        let tokens = Lexer::new(r##""Hello, {name}! \{\}" "{items["a"] + {"b": 1}["b"]}""##).collect::<Vec<Token>>();
+
+        assert_eq!(tokens[0].kind, TokenKind::Interpolated(vec![
+            Fragment::Literal("Hello, ".into()),
+            Fragment::Code(vec![Token::new(TokenKind::Identifier("name".into()), 1, (10, 14))]),
+            Fragment::Literal("! {}".into()),
+        ]));
+        assert_eq!(tokens[0].span, (1, 22));
+
+        let TokenKind::Interpolated(fragments) = &tokens[1].kind else { panic!() };
+        let Fragment::Code(code) = &fragments[0] else { panic!() };
+
+        assert_eq!(code.iter().map(|t| t.kind.clone()).collect::<Vec<TokenKind>>(), vec![
+            TokenKind::Identifier("items".into()),
+            TokenKind::LeftBracket,
+            TokenKind::String("a".into()),
+            TokenKind::RightBracket,
+            TokenKind::Plus,
+            TokenKind::LeftBrace,
+            TokenKind::String("b".into()),
+            TokenKind::Colon,
+            TokenKind::Number(1.0),
+            TokenKind::RightBrace,
+            TokenKind::LeftBracket,
+            TokenKind::String("b".into()),
+            TokenKind::RightBracket,
+        ]);
+        assert_eq!(tokens.len(), 2);
+    }
+
+    #[test]
+    fn labels() {
+        matches("'outer: while", vec![
+            TokenKind::Label("outer".into()),
+            TokenKind::Colon,
+            TokenKind::While,
+        ]);
+    }
+
+    #[test]
+    fn errors() {
+        matches("1 # 2 & 3", vec![
+            TokenKind::Number(1.0),
+            TokenKind::Error(LexError::UnexpectedCharacter('#')),
+            TokenKind::Number(2.0),
+            TokenKind::Error(LexError::UnexpectedCharacter('&')),
+            TokenKind::Number(3.0),
+        ]);
+
+        matches("1.2.3 items[1].length", vec![
+            TokenKind::Error(LexError::MalformedNumber("1.2.3".into())),
+            TokenKind::Identifier("items".into()),
+            TokenKind::LeftBracket,
+            TokenKind::Number(1.0),
+            TokenKind::RightBracket,
+            TokenKind::Dot,
+            TokenKind::Identifier("length".into()),
+        ]);
+
+        matches(r##""\q" 1 "unterminated"##, vec![
+            TokenKind::Error(LexError::InvalidEscape('q')),
+            TokenKind::Number(1.0),
+            TokenKind::Error(LexError::UnterminatedString),
+        ]);
+
+        matches("1 /* unterminated", vec![
+            TokenKind::Number(1.0),
+            TokenKind::Error(LexError::UnterminatedComment),
+        ]);
+    }
+
+    #[test]
+    fn error_spans() {
+        let tokens = Lexer::new("let a = \"ab\\qc\"").collect::<Vec<Token>>();
+
+        assert_eq!((tokens[3].line, tokens[3].span), (1, (12, 14)));
+
+        let errors = ["\"a {b\"\nlet c = \"d}\"", "\"a {} b\"", "\"a } b\"", "\"{a # b}\""].map(|source| {
+            let token = Lexer::new(source).next().unwrap();
+
+            (token.kind, token.span)
+        });
+
+        assert_eq!(errors, [
+            (TokenKind::Error(LexError::UnclosedInterpolation), (4, 5)),
+            (TokenKind::Error(LexError::EmptyInterpolation), (4, 6)),
+            (TokenKind::Error(LexError::UnmatchedBrace), (4, 5)),
+            (TokenKind::Error(LexError::UnexpectedCharacter('#')), (5, 6)),
+        ]);
+    }
+
+    fn matches(source: &str, expected: Vec<TokenKind>) {
+        let kinds = Lexer::new(source).map(|t| t.kind).collect::<Vec<TokenKind>>();
 
         assert_eq!(expected, kinds)
     }
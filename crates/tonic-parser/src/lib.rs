@@ -1,27 +1,39 @@
-#![feature(box_patterns)]
-
 mod token;
 mod lexer;
 mod parser;
 mod statement;
 mod expression;
 mod r#type;
+mod pattern;
 mod passes;
 
-pub use token::{TokenKind, Token, Span};
+pub use token::{TokenKind, Token, Fragment, Span, LexError};
 pub use lexer::Lexer;
-pub use statement::{Statement, Parameter};
-pub use expression::{Expression, Op};
+pub use statement::{Statement, Node, Parameter};
+pub use expression::{Expression, Op, Position};
 pub use r#type::Type;
+pub use pattern::{Pattern, Arm};
 pub use parser::{Parser, ParserError, ParserErrorType, Program};
 
-pub fn parse(source: &str) -> Result<Vec<Statement>, ParserError> {
+pub fn parse(source: &str) -> Result<Program, Vec<ParserError>> {
+    let (ast, errors) = parse_partial(source);
+
+    if errors.is_empty() {
+        Ok(ast)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse as much of the source code as possible, returning every syntax error alongside the statements
+/// that could be parsed. Useful for tooling that needs to report all of the problems in a file at once.
+pub fn parse_partial(source: &str) -> (Program, Vec<ParserError>) {
     let lexer = Lexer::new(source);
 
     let mut parser = Parser::new(lexer);
-    let mut ast = parser.parse()?;
+    let (mut ast, errors) = parser.parse_partial();
 
     passes::pass(&mut ast);
 
-    Ok(ast)
+    (ast, errors)
 }
\ No newline at end of file
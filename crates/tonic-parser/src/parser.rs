@@ -1,7 +1,8 @@
-use crate::{Statement, Expression, Token, TokenKind, Lexer, Type, Parameter, Span};
+use crate::{Statement, Node, Expression, Op, Position, Token, TokenKind, Fragment, Lexer, Type, Parameter, Pattern, Arm, Span, LexError};
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
-pub type Program = Vec<Statement>;
+pub type Program = Vec<Node>;
 
 #[derive(Debug, Clone)]
 pub struct ParserError {
@@ -15,51 +16,151 @@ pub enum ParserErrorType {
     InvalidBreakableScope,
     InvalidContinuableScope,
     UnexpectedToken(String, Option<String>),
-    NestedFunctionDefinition,
     ExpectedIdentifier,
+    UndefinedLabel(String),
+    DuplicateLabel(String),
+    InvalidToken(LexError),
+}
+
+impl Display for ParserErrorType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidBreakableScope => write!(f, "`break` can only be used inside of a loop"),
+            Self::InvalidContinuableScope => write!(f, "`continue` can only be used inside of a loop"),
+            Self::UnexpectedToken(found, _) if found == "Eof" => write!(f, "unexpected end of file"),
+            Self::UnexpectedToken(found, Some(expected)) => write!(f, "unexpected token `{}`, expected `{}`", found, expected),
+            Self::UnexpectedToken(found, None) => write!(f, "unexpected token `{}`", found),
+            Self::ExpectedIdentifier => write!(f, "expected an identifier"),
+            Self::UndefinedLabel(label) => write!(f, "use of undeclared label `'{}`", label),
+            Self::DuplicateLabel(label) => write!(f, "label `'{}` is already in use by an enclosing loop", label),
+            Self::InvalidToken(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Display for ParserError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.err)
+    }
 }
 
 type BindingPower = u8;
 type ParserResult<T> = Result<T, ParserError>;
 
+/// Where the parser reads its tokens from. Expressions that are embedded in a string have already been lexed, so
+/// they're parsed from the tokens that the lexer produced for them.
+#[derive(Debug)]
+enum Tokens<'p> {
+    Lexer(Lexer<'p>),
+    Embedded(std::vec::IntoIter<Token>),
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        match self {
+            Tokens::Lexer(lexer) => lexer.next(),
+            Tokens::Embedded(tokens) => tokens.next(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Parser<'p> {
-    lexer: Lexer<'p>,
+    tokens: Tokens<'p>,
+    previous: Token,
     current: Token,
     peek: Token,
-    in_breakable_scope: bool,
-    scope_depth: usize,
+    /// The loops that enclose the current statement, along with their labels.
+    loops: Vec<Option<String>>,
+    errors: Vec<ParserError>,
 }
 
 #[allow(dead_code)]
 impl<'p> Parser<'p> {
     pub fn new(lexer: Lexer<'p>) -> Self {
+        Self::from_tokens(Tokens::Lexer(lexer))
+    }
+
+    fn from_tokens(tokens: Tokens<'p>) -> Self {
         Self {
-            lexer,
+            tokens,
+            previous: Token::eof(),
             current: Token::eof(),
             peek: Token::eof(),
-            in_breakable_scope: false,
-            scope_depth: 0,
+            loops: Vec::new(),
+            errors: Vec::new(),
         }
     }
 
     pub fn read(&mut self) {
-        self.current = std::mem::replace(&mut self.peek, if let Some(t) = self.lexer.next() { t } else { Token::eof() });
+        // Doc comments are trivia as far as the parser is concerned.
+        let next = match self.tokens.find(|t| ! matches!(t.kind, TokenKind::DocComment(_))) {
+            Some(t) => t,
+            // Give the `Eof` token the position of the last real token so that errors such as a missing `}`
+            // can still point somewhere useful.
+            None => Token::new(TokenKind::Eof, self.peek.line, (self.peek.span.1, self.peek.span.1)),
+        };
+
+        self.previous = std::mem::replace(&mut self.current, std::mem::replace(&mut self.peek, next));
+    }
+
+    /// Parse a statement and wrap it in a `Node` with its position.
+    fn parse_node(&mut self) -> ParserResult<Node> {
+        let start = (self.current.line, self.current.span);
+        let statement = self.parse_statement()?;
+
+        Ok(self.node(statement, start))
+    }
+
+    /// Create a `Node` for a statement that started at `start` and ends at the previous token.
+    fn node(&self, statement: Statement, start: (usize, Span)) -> Node {
+        let Position { line, span } = self.position(start);
+
+        Node::new(statement, line, span)
+    }
+
+    /// The position of something that started at `start` and ends at the previous token, or at the end of its first
+    /// line if it runs over more than one.
+    fn position(&self, (line, span): (usize, Span)) -> Position {
+        let end = if self.previous.line == line {
+            self.previous.span.1.max(span.1)
+        } else {
+            span.1
+        };
+
+        Position::new(line, (span.0, end))
     }
 
     fn parse_statement(&mut self) -> ParserResult<Statement> {
-        Ok(match self.current.kind {
-            TokenKind::Let => self.parse_let()?,
+        Ok(match self.current.kind.clone() {
+            TokenKind::Let | TokenKind::Const => self.parse_let()?,
             TokenKind::Fn => self.parse_fn()?,
             TokenKind::If => self.parse_if()?,
-            TokenKind::While => self.parse_while()?,
+            TokenKind::While => self.parse_while(None)?,
+            TokenKind::For => self.parse_for(None)?,
+            TokenKind::Label(label) => {
+                self.read();
+                self.expect(TokenKind::Colon)?;
+
+                if self.loops.contains(&Some(label.clone())) {
+                    return Err(self.error(ParserErrorType::DuplicateLabel(label)));
+                }
+
+                match self.current.kind {
+                    TokenKind::While => self.parse_while(Some(label))?,
+                    TokenKind::For => self.parse_for(Some(label))?,
+                    _ => return Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some("While".to_owned())))),
+                }
+            },
             TokenKind::Use => {
                 self.read();
 
                 let mut imports = Vec::new();
 
                 while self.current.kind != TokenKind::From {
-                    if imports.len() > 0 {
+                    if ! imports.is_empty() {
                         self.expect(TokenKind::Comma)?;
                     }
 
@@ -78,22 +179,22 @@ impl<'p> Parser<'p> {
                 }
             },
             TokenKind::Break => {
-                if ! self.in_breakable_scope {
-                    return Err(ParserError { line: self.current.line, span: self.current.span, err: ParserErrorType::InvalidBreakableScope })
+                if self.loops.is_empty() {
+                    return Err(self.error(ParserErrorType::InvalidBreakableScope))
                 }
 
-                self.read();
+                let label = self.jump_label()?;
 
-                Statement::Break
+                Statement::Break { label }
             },
             TokenKind::Continue => {
-                if ! self.in_breakable_scope {
-                    return Err(ParserError { line: self.current.line, span: self.current.span, err: ParserErrorType::InvalidContinuableScope })
+                if self.loops.is_empty() {
+                    return Err(self.error(ParserErrorType::InvalidContinuableScope))
                 }
 
-                self.read();
+                let label = self.jump_label()?;
 
-                Statement::Continue
+                Statement::Continue { label }
             },
             TokenKind::Return => {
                 self.read();
@@ -102,43 +203,40 @@ impl<'p> Parser<'p> {
 
                 Statement::Return { expression }
             },
+            TokenKind::Type => {
+                self.read();
+
+                let identifier = self.identifier()?;
+
+                self.expect(TokenKind::Equals)?;
+
+                let r#type = self.type_expression()?;
+
+                Statement::TypeAlias { identifier, r#type }
+            },
             _ => {
                 Statement::Expression { expression: self.expression(0)? }
             },
         })
     }
 
+    /// Parse a `let`, or a `const`, which is written the same way.
     fn parse_let(&mut self) -> ParserResult<Statement> {
+        let constant = self.current.kind == TokenKind::Const;
+
         self.read();
 
-        let identifier = self.identifier()?;
-        let mut r#type = self.r#type()?;
+        let pattern = self.pattern()?;
+        let r#type = self.r#type()?;
 
         self.expect(TokenKind::Equals)?;
 
         let expression = self.expression(0)?;
 
-        match expression {
-            Expression::String(_) => r#type = Some(Type::String),
-            Expression::Number(_) => r#type = Some(Type::Number),
-            Expression::Bool(_) => r#type = Some(Type::Bool),
-            _ => (),
-        };
-
-        Ok(Statement::Let { identifier, r#type, initial: expression })
+        Ok(Statement::Let { pattern, r#type, initial: expression, constant })
     }
 
     fn parse_fn(&mut self) -> ParserResult<Statement> {
-        if self.scope_depth > 0 {
-            return Err(ParserError {
-                line: self.current.line,
-                span: self.current.span,
-                err: ParserErrorType::NestedFunctionDefinition,
-            });
-        }
-
-        self.scope_depth += 1;
-
         self.read();
 
         let identifier = self.identifier()?;
@@ -151,13 +249,7 @@ impl<'p> Parser<'p> {
 
         let return_type = self.r#type()?;
 
-        self.expect(TokenKind::LeftBrace)?;
-
-        let body = self.block(TokenKind::RightBrace)?;
-
-        self.expect(TokenKind::RightBrace)?;
-
-        self.scope_depth -= 1;
+        let body = self.function_body()?;
 
         Ok(Statement::Function {
             identifier, parameters, return_type, body
@@ -182,7 +274,10 @@ impl<'p> Parser<'p> {
 
             // If we see another `if` token, then we're going to parse an `else if` statement.
             if self.current.kind == TokenKind::If {
-                otherwise = vec![self.parse_if()?];
+                let start = (self.current.line, self.current.span);
+                let statement = self.parse_if()?;
+
+                otherwise = vec![self.node(statement, start)];
             } else {
                 self.expect(TokenKind::LeftBrace)?;
 
@@ -195,22 +290,118 @@ impl<'p> Parser<'p> {
         Ok(Statement::If { condition, then, otherwise })
     }
 
-    fn parse_while(&mut self) -> ParserResult<Statement> {
+    fn parse_while(&mut self, label: Option<String>) -> ParserResult<Statement> {
         self.read();
 
         let condition = self.expression(0)?;
 
         self.expect(TokenKind::LeftBrace)?;
-        
-        self.in_breakable_scope = true;
+
+        self.loops.push(label.clone());
 
         let then = self.block(TokenKind::RightBrace)?;
 
+        self.loops.pop();
+
+        self.expect(TokenKind::RightBrace)?;
+
+        Ok(Statement::While { label, condition, then })
+    }
+
+    fn parse_for(&mut self, label: Option<String>) -> ParserResult<Statement> {
+        self.read();
+
+        let mut key = None;
+        let mut value = self.pattern()?;
+
+        if self.current.kind == TokenKind::Comma {
+            // The key of a map entry is always a string, so it can only be bound to a name.
+            let Pattern::Binding(name) = value else {
+                return Err(self.error(ParserErrorType::ExpectedIdentifier))
+            };
+
+            self.read();
+
+            key = Some(name);
+            value = self.pattern()?;
+        }
+
+        self.expect(TokenKind::In)?;
+
+        let iterable = self.expression(0)?;
+
+        self.expect(TokenKind::LeftBrace)?;
+
+        self.loops.push(label.clone());
+
+        let then = self.block(TokenKind::RightBrace)?;
+
+        self.loops.pop();
+
+        self.expect(TokenKind::RightBrace)?;
+
+        Ok(Statement::For { label, key, value, iterable, then })
+    }
+
+    /// Parse the optional label after a `break` or `continue`, making sure that it belongs to one of
+    /// the enclosing loops.
+    fn jump_label(&mut self) -> ParserResult<Option<String>> {
+        let line = self.current.line;
+
+        self.read();
+
+        match self.current.kind.clone() {
+            // A label on the next line belongs to a new loop, not to this statement.
+            TokenKind::Label(label) if self.current.line == line => {
+                if self.loops.contains(&Some(label.clone())) {
+                    self.read();
+
+                    return Ok(Some(label));
+                }
+
+                // Skip over the label so that recovery doesn't mistake it for the start of a labelled loop.
+                let error = self.error(ParserErrorType::UndefinedLabel(label));
+
+                self.read();
+
+                Err(error)
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse the `{ ... }` body of a function or closure. Loops outside of the function can't be
+    /// broken out of from inside of it.
+    fn function_body(&mut self) -> ParserResult<Vec<Node>> {
+        let loops = std::mem::take(&mut self.loops);
+
+        self.expect(TokenKind::LeftBrace)?;
+
+        let body = self.block(TokenKind::RightBrace);
+
+        self.loops = loops;
+
         self.expect(TokenKind::RightBrace)?;
 
-        self.in_breakable_scope = false;
+        body
+    }
+
+    /// Create a `ParserError` at the current token.
+    /// 
+    /// If the current token is one that the lexer couldn't make sense of, the lexer's error is reported instead
+    /// since it's far more useful than being told that the token was unexpected.
+    fn error(&self, err: ParserErrorType) -> ParserError {
+        let err = match &self.current.kind {
+            TokenKind::Error(error) => ParserErrorType::InvalidToken(error.clone()),
+            _ => err,
+        };
 
-        Ok(Statement::While { condition, then })
+        ParserError { line: self.current.line, span: self.current.span, err }
+    }
+
+    /// The operator that the current token stands for, such as `+` for an addition.
+    fn op(&self) -> ParserResult<Op> {
+        Op::try_from(&self.current.kind).map_err(|kind| self.error(ParserErrorType::UnexpectedToken(format!("{:?}", kind), None)))
     }
 
     fn expect(&mut self, kind: TokenKind) -> ParserResult<()> {
@@ -219,17 +410,24 @@ impl<'p> Parser<'p> {
 
             Ok(())
         } else {
-            return Err(ParserError { line: self.current.line, span: self.current.span, err: ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some(format!("{:?}", kind))) })
+            Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some(format!("{:?}", kind)))))
         }
     }
 
     fn expression(&mut self, bp: u8) -> ParserResult<Expression> {
+        let start = (self.current.line, self.current.span);
+
         let mut lhs = match self.current.kind.clone() {
             TokenKind::Number(n) => {
                 self.read();
 
                 Expression::Number(n)
             },
+            TokenKind::BigInt(n) => {
+                self.read();
+
+                Expression::BigInt(n)
+            },
             TokenKind::True => {
                 self.read();
 
@@ -240,11 +438,26 @@ impl<'p> Parser<'p> {
 
                 Expression::Bool(false)
             },
+            TokenKind::Null => {
+                self.read();
+
+                Expression::Null
+            },
             TokenKind::String(s) => {
                 self.read();
 
                 Expression::String(s)
             },
+            TokenKind::Interpolated(fragments) => {
+                self.read();
+
+                let parts = fragments.into_iter().map(|fragment| match fragment {
+                    Fragment::Literal(s) => Ok(Expression::String(s)),
+                    Fragment::Code(tokens) => self.embedded(tokens),
+                }).collect::<ParserResult<Vec<Expression>>>()?;
+
+                Expression::Interpolated(parts)
+            },
             TokenKind::Identifier(i) => {
                 self.read();
 
@@ -302,17 +515,12 @@ impl<'p> Parser<'p> {
                 let body = if self.current.kind == TokenKind::Arrow {
                     self.read();
 
+                    let start = (self.current.line, self.current.span);
                     let expression = self.expression(0)?;
 
-                    vec![Statement::Return { expression }]
+                    vec![self.node(Statement::Return { expression }, start)]
                 } else {
-                    self.expect(TokenKind::LeftBrace)?;
-                    
-                    let body = self.block(TokenKind::RightBrace)?;
-
-                    self.expect(TokenKind::RightBrace)?;
-
-                    body
+                    self.function_body()?
                 };
 
                 Expression::Closure(params, body)
@@ -326,8 +534,40 @@ impl<'p> Parser<'p> {
 
                 expression
             },
+            TokenKind::If => {
+                let Statement::If { condition, then, otherwise } = self.parse_if()? else { unreachable!() };
+
+                Expression::If { condition: condition.boxed(), then, otherwise, position: self.position(start) }
+            },
+            TokenKind::Match => {
+                self.read();
+
+                let subject = self.expression(0)?;
+
+                self.expect(TokenKind::LeftBrace)?;
+
+                let mut arms = Vec::new();
+
+                while self.current.kind != TokenKind::RightBrace && self.current.kind != TokenKind::Eof {
+                    arms.push(self.arm()?);
+
+                    if self.current.kind == TokenKind::Comma {
+                        self.read();
+                    }
+                }
+
+                self.expect(TokenKind::RightBrace)?;
+
+                Expression::Match(subject.boxed(), arms, self.position(start))
+            },
+            TokenKind::DoubleDot | TokenKind::DoubleDotEquals => {
+                let (_, rbp) = infix_binding_power(&TokenKind::DoubleDot).unwrap();
+
+                self.range(None, rbp)?
+            },
             _ if is_prefix(&self.current.kind) => {
                 let kind = self.current.kind.clone();
+                let op = self.op()?;
 
                 self.read();
 
@@ -335,9 +575,9 @@ impl<'p> Parser<'p> {
 
                 let rhs = self.expression(rbp)?;
 
-                prefix(&kind, rhs)
+                prefix(op, rhs)
             },
-            _ => todo!("{:?}", self.current.kind),
+            _ => return Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), None))),
         };
 
         loop {
@@ -364,11 +604,19 @@ impl<'p> Parser<'p> {
                     break;
                 }
 
+                if matches!(op, TokenKind::DoubleDot | TokenKind::DoubleDotEquals) {
+                    lhs = self.range(Some(lhs), rbp)?;
+
+                    continue;
+                }
+
+                let op = self.op()?;
+
                 self.read();
 
                 let rhs = self.expression(rbp)?;
 
-                lhs = infix(lhs, &op, rhs);
+                lhs = infix(lhs, op, rhs, self.position(start));
 
                 continue;
             }
@@ -386,7 +634,27 @@ impl<'p> Parser<'p> {
 
                 Ok(i)
             },
-            _ => Err(ParserError { line: self.current.line, span: self.current.span, err: ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some("String".to_owned())) })
+            _ => Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some("String".to_owned()))))
+        }
+    }
+
+    /// Parse the tokens of an expression that's embedded in an interpolated string, which have to make up exactly
+    /// one expression.
+    fn embedded(&mut self, tokens: Vec<Token>) -> ParserResult<Expression> {
+        let mut parser = Parser::from_tokens(Tokens::Embedded(tokens.into_iter()));
+        parser.loops = self.loops.clone();
+
+        parser.read();
+        parser.read();
+
+        let expression = parser.expression(0);
+
+        self.errors.append(&mut parser.errors);
+
+        match parser.current.kind {
+            TokenKind::Eof => expression,
+            _ if expression.is_err() => expression,
+            _ => Err(parser.error(ParserErrorType::UnexpectedToken(format!("{:?}", parser.current.kind), None))),
         }
     }
 
@@ -397,7 +665,7 @@ impl<'p> Parser<'p> {
 
                 Ok(i)
             },
-            _ => Err(ParserError { line: self.current.line, span: self.current.span, err: ParserErrorType::ExpectedIdentifier })
+            _ => Err(self.error(ParserErrorType::ExpectedIdentifier))
         }
     }
 
@@ -418,108 +686,481 @@ impl<'p> Parser<'p> {
             }
         }
 
-        Ok(args)
-    }
+        Ok(args)
+    }
+
+    fn parameters(&mut self) -> ParserResult<Vec<Parameter>> {
+        let mut parameters = Vec::new();
+
+        loop {
+            if self.current.kind == TokenKind::RightParen {
+                break;
+            }
+
+            let pattern = self.pattern()?;
+            let r#type = self.r#type()?;
+
+            parameters.push(Parameter::with_pattern(pattern, r#type));
+
+            if self.current.kind == TokenKind::Comma {
+                self.read();
+            }
+        }
+
+        Ok(parameters)
+    }
+
+    fn block(&mut self, end: TokenKind) -> ParserResult<Vec<Node>> {
+        let mut block = Vec::new();
+
+        while self.current.kind != end && self.current.kind != TokenKind::Eof {
+            if let Some(statement) = self.parse_statement_or_recover() {
+                block.push(statement);
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Parse a single statement. If the statement contains a syntax error, the error is recorded and
+    /// the parser skips ahead to the start of the next statement so that parsing can carry on.
+    fn parse_statement_or_recover(&mut self) -> Option<Node> {
+        let start = (self.current.line, self.current.span);
+        let loops = self.loops.clone();
+
+        match self.parse_node() {
+            Ok(node) => Some(node),
+            Err(error) => {
+                self.errors.push(error);
+
+                self.loops = loops;
+                self.synchronize(start);
+
+                None
+            }
+        }
+    }
+
+    /// Skip tokens until we reach a token that can start (or end) a statement.
+    /// 
+    /// If the statement failed on its very first token, that token is skipped as well so that we're
+    /// guaranteed to make progress.
+    fn synchronize(&mut self, start: (usize, Span)) {
+        while ! is_synchronization_point(&self.current.kind) {
+            self.read();
+        }
+
+        if self.current.kind != TokenKind::Eof && (self.current.line, self.current.span) == start {
+            self.read();
+
+            self.synchronize(start);
+        }
+    }
+
+    /// Parse the rest of a range, starting at its `..` or `..=`. The end can be left out of an exclusive range,
+    /// and a `step` can follow the end.
+    fn range(&mut self, start: Option<Expression>, rbp: BindingPower) -> ParserResult<Expression> {
+        let inclusive = self.current.kind == TokenKind::DoubleDotEquals;
+
+        self.read();
+
+        // A range with no end can finish a line, so whatever comes on the next line starts a new statement.
+        let end = if ends_range(&self.current.kind) || self.current.line != self.previous.line || self.at_step() {
+            if inclusive {
+                return Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), None)));
+            }
+
+            None
+        } else {
+            Some(self.expression(rbp)?.boxed())
+        };
+
+        let step = if self.at_step() {
+            self.read();
+
+            Some(self.expression(rbp)?.boxed())
+        } else {
+            None
+        };
+
+        Ok(Expression::Range { start: start.map(Expression::boxed), end, inclusive, step })
+    }
+
+    /// Whether the current token is the `step` of a range. `step` isn't a keyword, so it's only treated as one when
+    /// another expression follows it on the same line, leaving `0..step` free to use a variable called `step`.
+    fn at_step(&self) -> bool {
+        self.current.kind == TokenKind::Identifier("step".to_owned()) && self.peek.line == self.current.line && ! ends_range(&self.peek.kind)
+    }
+
+    /// Parse a `pattern if guard => body` arm. The body is either a block or a single expression, so a map
+    /// literal needs wrapping in parentheses.
+    /// 
+    /// An expression needs a `,` after it (unless it's the last arm) since something like `[a, b]` on the next line
+    /// would otherwise be read as an index.
+    fn arm(&mut self) -> ParserResult<Arm> {
+        let pattern = self.pattern()?;
+
+        let guard = if self.current.kind == TokenKind::If {
+            self.read();
+
+            Some(self.expression(0)?)
+        } else {
+            None
+        };
+
+        self.expect(TokenKind::FatArrow)?;
+
+        let body = if self.current.kind == TokenKind::LeftBrace {
+            self.read();
+
+            let body = self.block(TokenKind::RightBrace)?;
+
+            self.expect(TokenKind::RightBrace)?;
+
+            body
+        } else {
+            let start = (self.current.line, self.current.span);
+            let expression = self.expression(0)?;
+            let body = vec![self.node(Statement::Expression { expression }, start)];
+
+            if self.current.kind != TokenKind::RightBrace {
+                self.expect(TokenKind::Comma)?;
+            }
+
+            body
+        };
+
+        Ok(Arm { pattern, guard, body })
+    }
+
+    /// Parse a pattern, where `|` binds more loosely than anything else.
+    fn pattern(&mut self) -> ParserResult<Pattern> {
+        let mut patterns = vec![self.primary_pattern()?];
+
+        while self.current.kind == TokenKind::Pipe {
+            self.read();
+
+            patterns.push(self.primary_pattern()?);
+        }
+
+        Ok(if patterns.len() == 1 { patterns.remove(0) } else { Pattern::Or(patterns) })
+    }
+
+    fn primary_pattern(&mut self) -> ParserResult<Pattern> {
+        let pattern = match self.current.kind.clone() {
+            TokenKind::String(s) => Pattern::String(s),
+            TokenKind::Number(n) => Pattern::Number(n),
+            TokenKind::BigInt(n) => Pattern::BigInt(n),
+            TokenKind::True => Pattern::Bool(true),
+            TokenKind::False => Pattern::Bool(false),
+            TokenKind::Null => Pattern::Null,
+            TokenKind::Identifier(i) if i == "_" => Pattern::Wildcard,
+            TokenKind::Identifier(i) => Pattern::Binding(i),
+            TokenKind::Minus => {
+                self.read();
+
+                return match self.current.kind.clone() {
+                    TokenKind::Number(n) => {
+                        self.read();
+
+                        Ok(Pattern::Number(-n))
+                    },
+                    TokenKind::BigInt(n) => {
+                        self.read();
+
+                        Ok(Pattern::BigInt(format!("-{}", n)))
+                    },
+                    _ => Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some("Number".to_owned())))),
+                }
+            },
+            TokenKind::LeftBracket => {
+                self.read();
+
+                let mut items = Vec::new();
+
+                while self.current.kind != TokenKind::RightBracket {
+                    if self.current.kind == TokenKind::Ellipsis {
+                        items.push(self.rest_pattern()?);
+
+                        // The rest of the array has to come last, so nothing else can follow it.
+                        if self.current.kind == TokenKind::Comma {
+                            self.read();
+                        }
+
+                        break;
+                    }
+
+                    items.push(self.pattern()?);
+
+                    if self.current.kind != TokenKind::Comma {
+                        break;
+                    }
+
+                    self.read();
+                }
+
+                self.expect(TokenKind::RightBracket)?;
+
+                return Ok(Pattern::Array(items))
+            },
+            TokenKind::LeftBrace => {
+                self.read();
+
+                let mut members = Vec::new();
+
+                while self.current.kind != TokenKind::RightBrace {
+                    let key = self.string()?;
+
+                    self.expect(TokenKind::Colon)?;
+
+                    members.push((key, self.pattern()?));
+
+                    if self.current.kind != TokenKind::Comma {
+                        break;
+                    }
+
+                    self.read();
+                }
+
+                self.expect(TokenKind::RightBrace)?;
+
+                return Ok(Pattern::Map(members))
+            },
+            _ => return Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), None))),
+        };
+
+        self.read();
+
+        Ok(pattern)
+    }
+
+    /// Parse `...rest`, or `...` on its own to ignore the rest of an array.
+    fn rest_pattern(&mut self) -> ParserResult<Pattern> {
+        self.expect(TokenKind::Ellipsis)?;
+
+        Ok(match self.current.kind.clone() {
+            TokenKind::Identifier(i) if i == "_" => {
+                self.read();
+
+                Pattern::Rest(None)
+            },
+            TokenKind::Identifier(i) => {
+                self.read();
+
+                Pattern::Rest(Some(i))
+            },
+            _ => Pattern::Rest(None),
+        })
+    }
+
+    fn r#type(&mut self) -> ParserResult<Option<Type>> {
+        if self.current.kind != TokenKind::Colon && self.current.kind != TokenKind::DoubleColon {
+            Ok(None)
+        } else {
+            self.read();
+
+            Ok(Some(self.type_expression()?))
+        }
+    }
+
+    /// Parse a type, where `|` binds more loosely than a trailing `?`.
+    fn type_expression(&mut self) -> ParserResult<Type> {
+        let mut types = vec![self.optional_type()?];
+
+        while self.current.kind == TokenKind::Pipe {
+            self.read();
+
+            types.push(self.optional_type()?);
+        }
+
+        Ok(if types.len() == 1 { types.remove(0) } else { Type::Union(types) })
+    }
+
+    fn optional_type(&mut self) -> ParserResult<Type> {
+        let mut r#type = self.primary_type()?;
+
+        while self.current.kind == TokenKind::Question {
+            self.read();
+
+            r#type = Type::Optional(Box::new(r#type));
+        }
+
+        Ok(r#type)
+    }
+
+    fn primary_type(&mut self) -> ParserResult<Type> {
+        Ok(match self.current.kind.clone() {
+            TokenKind::Identifier(name) => {
+                self.read();
+
+                Type::string(name)
+            },
+            TokenKind::LeftBracket => {
+                self.read();
+
+                let item = self.type_expression()?;
+
+                self.expect(TokenKind::RightBracket)?;
+
+                Type::Array(Box::new(item))
+            },
+            // A map type uses a type for its keys, `{string: number}`, whereas a record type lists its keys, `{"x": number}`.
+            TokenKind::LeftBrace => {
+                self.read();
+
+                let r#type = if let TokenKind::String(_) = self.current.kind {
+                    let mut fields = Vec::new();
+
+                    while self.current.kind != TokenKind::RightBrace {
+                        let key = self.string()?;
+
+                        self.expect(TokenKind::Colon)?;
+
+                        fields.push((key, self.type_expression()?));
+
+                        if self.current.kind == TokenKind::Comma {
+                            self.read();
+                        }
+                    }
+
+                    Type::Record(fields)
+                } else {
+                    let key = self.type_expression()?;
+
+                    self.expect(TokenKind::Colon)?;
+
+                    let value = self.type_expression()?;
+
+                    Type::Map(Box::new(key), Box::new(value))
+                };
+
+                self.expect(TokenKind::RightBrace)?;
+
+                r#type
+            },
+            TokenKind::Fn => {
+                self.read();
+                self.expect(TokenKind::LeftParen)?;
+
+                let mut parameters = Vec::new();
+
+                while self.current.kind != TokenKind::RightParen {
+                    parameters.push(self.type_expression()?);
 
-    fn parameters(&mut self) -> ParserResult<Vec<Parameter>> {
-        let mut parameters = Vec::new();
+                    if self.current.kind != TokenKind::RightParen {
+                        self.expect(TokenKind::Comma)?;
+                    }
+                }
 
-        loop {
-            if self.current.kind == TokenKind::RightParen {
-                break;
-            }
+                self.expect(TokenKind::RightParen)?;
 
-            let identifier = self.identifier()?;
-            let r#type = self.r#type()?;
+                let return_type = if self.current.kind == TokenKind::Arrow {
+                    self.read();
 
-            parameters.push(Parameter::new(identifier, r#type));
+                    Some(Box::new(self.optional_type()?))
+                } else {
+                    None
+                };
 
-            if self.current.kind == TokenKind::Comma {
+                Type::Function(parameters, return_type)
+            },
+            TokenKind::LeftParen => {
                 self.read();
-            }
-        }
-
-        Ok(parameters)
-    }
 
-    fn block(&mut self, end: TokenKind) -> ParserResult<Vec<Statement>> {
-        let mut block = Vec::new();
+                let r#type = self.type_expression()?;
 
-        while self.current.kind != end {
-            block.push(self.parse_statement()?);
-        }
+                self.expect(TokenKind::RightParen)?;
 
-        Ok(block)
+                r#type
+            },
+            _ => return Err(self.error(ParserErrorType::UnexpectedToken(format!("{:?}", self.current.kind), Some("Type".to_owned())))),
+        })
     }
 
-    fn r#type(&mut self) -> ParserResult<Option<Type>> {
-        if self.current.kind != TokenKind::Colon && self.current.kind != TokenKind::DoubleColon {
-            Ok(None)
-        } else {
-            self.read();
-
-            let r#type = self.identifier()?;
-            
-            if ! Type::valid(&r#type) {
-                return Ok(None)
-            }
+    /// Parse the entire program, returning every syntax error if there were any.
+    pub fn parse(&mut self) -> Result<Program, Vec<ParserError>> {
+        let (program, errors) = self.parse_partial();
 
-            Ok(Some(Type::string(r#type)))
+        if errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(errors)
         }
     }
 
-    pub fn parse(&mut self) -> ParserResult<Program> {
+    /// Parse as much of the program as possible, returning the statements that could be parsed alongside
+    /// every syntax error that was found.
+    pub fn parse_partial(&mut self) -> (Program, Vec<ParserError>) {
         let mut program = Vec::new();
 
         self.read();
         self.read();
 
         while self.current.kind != TokenKind::Eof {
-            program.push(self.parse_statement()?);
+            if let Some(statement) = self.parse_statement_or_recover() {
+                program.push(statement);
+            }
         }
 
-        Ok(program)
+        (program, std::mem::take(&mut self.errors))
     }
 }
 
+fn is_synchronization_point(kind: &TokenKind) -> bool {
+    matches!(kind,
+        TokenKind::Let | TokenKind::Const | TokenKind::Fn | TokenKind::If | TokenKind::While | TokenKind::For | TokenKind::Use | TokenKind::Type | TokenKind::Label(_) |
+        TokenKind::Return | TokenKind::Break | TokenKind::Continue | TokenKind::RightBrace | TokenKind::Eof
+    )
+}
+
+/// Whether a token can follow a range that has no end, such as the `]` in `items[1..]`.
+fn ends_range(kind: &TokenKind) -> bool {
+    matches!(kind, TokenKind::RightBracket | TokenKind::RightParen | TokenKind::LeftBrace | TokenKind::RightBrace | TokenKind::Comma | TokenKind::Eof)
+}
+
 fn is_prefix(kind: &TokenKind) -> bool {
     [TokenKind::Minus, TokenKind::Not].contains(kind)
 }
 
 fn prefix_binding_power(kind: &TokenKind) -> ((), u8) {
     match kind {
-        TokenKind::Minus | TokenKind::Not => ((), 99),
+        TokenKind::Minus | TokenKind::Not => ((), 15),
         _ => unreachable!()
     }
 }
 
-fn prefix(kind: &TokenKind, rhs: Expression) -> Expression {
-    Expression::Prefix(kind.into(), Box::new(rhs))
+fn prefix(op: Op, rhs: Expression) -> Expression {
+    Expression::Prefix(op, Box::new(rhs))
 }
 
 fn infix_binding_power(kind: &TokenKind) -> Option<(BindingPower, BindingPower)> {
     Some(match kind {
-        TokenKind::Asterisk | TokenKind::Slash | TokenKind::Percent | TokenKind::DoubleAsterisk => (13, 14),
+        // `**` is right-associative and binds more tightly than a prefix `-`, so `-2 ** 2` is `-(2 ** 2)`.
+        TokenKind::DoubleAsterisk => (17, 16),
+        TokenKind::Asterisk | TokenKind::Slash | TokenKind::Percent => (13, 14),
         TokenKind::Plus | TokenKind::Minus => (11, 12),
         TokenKind::GreaterThan | TokenKind::GreaterThanEquals | TokenKind::LessThan | TokenKind::LessThanEquals => (9, 10),
+        // `0..n + 1` is a range up to `n + 1`, but a range binds more tightly than a comparison.
+        TokenKind::DoubleDot | TokenKind::DoubleDotEquals => (10, 11),
         TokenKind::EqualsEquals | TokenKind::NotEquals => (7, 8),
         TokenKind::And => (5, 6),
-        TokenKind::Or => (3, 4),
-        TokenKind::Equals | TokenKind::PlusEquals | TokenKind::MinusEquals | TokenKind::AsteriskEquals | TokenKind::SlashEquals => (2, 1),
+        TokenKind::Or | TokenKind::DoubleQuestion => (3, 4),
+        TokenKind::Equals | TokenKind::PlusEquals | TokenKind::MinusEquals | TokenKind::AsteriskEquals | TokenKind::SlashEquals | TokenKind::DoubleAsteriskEquals
+            | TokenKind::DoubleQuestionEquals => (2, 1),
         _ => return None
     })
 }
 
-fn infix(lhs: Expression, kind: &TokenKind, rhs: Expression) -> Expression {
-    match kind {
-        TokenKind::Equals => Expression::Assign(lhs.boxed(), rhs.boxed()),
-        _ => Expression::Infix(lhs.boxed(), kind.into(), rhs.boxed())
+fn infix(lhs: Expression, op: Op, rhs: Expression, position: Position) -> Expression {
+    match op {
+        Op::Assign => Expression::Assign(lhs.boxed(), rhs.boxed(), position),
+        _ => Expression::Infix(lhs.boxed(), op, rhs.boxed(), position)
     }
 }
 
 fn postfix_binding_power(kind: &TokenKind) -> Option<(BindingPower, ())> {
     Some(match kind {
-        TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::Dot => (19, ()),
+        TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::Dot | TokenKind::QuestionDot => (19, ()),
         _ => return None
     })
 }
@@ -551,14 +1192,27 @@ fn postfix(parser: &mut Parser, lhs: Expression, kind: &TokenKind) -> ParserResu
 
             Ok(Expression::Dot(lhs.boxed(), path.boxed()))
         },
-        _ => todo!()
+        TokenKind::QuestionDot if parser.current.kind == TokenKind::LeftBracket => {
+            parser.read();
+
+            let property = parser.expression(0)?;
+
+            parser.expect(TokenKind::RightBracket)?;
+
+            Ok(Expression::OptionalIndex(lhs.boxed(), property.boxed()))
+        },
+        TokenKind::QuestionDot => {
+            let path = parser.expression(19)?;
+
+            Ok(Expression::OptionalDot(lhs.boxed(), path.boxed()))
+        },
+        _ => unreachable!()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Op;
 
     macro_rules! map {
         ($($key:expr => $value:expr),+) => {
@@ -581,7 +1235,7 @@ mod tests {
                 expression: Expression::Closure(vec![], vec![
                     Statement::Return {
                         expression: Expression::String("testing".to_owned())
-                    }
+                    }.into()
                 ])
             }
         ]);
@@ -623,6 +1277,19 @@ mod tests {
                 ], vec![])
             }
         ]);
+
+        assert_eq!(parse(r##"
+            (fn ([key, value], {"x": x}: Point) {
+
+            })
+        "##), vec![
+            Statement::Expression {
+                expression: Expression::Closure(vec![
+                    Parameter::with_pattern(Pattern::Array(vec![Pattern::Binding("key".into()), Pattern::Binding("value".into())]), None),
+                    Parameter::with_pattern(Pattern::Map(vec![("x".into(), Pattern::Binding("x".into()))]), Some(Type::Named("Point".into()))),
+                ], vec![])
+            }
+        ]);
     }
 
     #[test]
@@ -686,23 +1353,82 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn interpolated_strings() {
+        assert_eq!(parse(r#""Hello, {user.name}! You have {count + 1} messages""#), vec![
+            Statement::Expression {
+                expression: Expression::Interpolated(vec![
+                    Expression::String("Hello, ".into()),
+                    Expression::Dot(Expression::Identifier("user".into()).boxed(), Expression::Identifier("name".into()).boxed()),
+                    Expression::String("! You have ".into()),
+                    Expression::Infix(Expression::Identifier("count".into()).boxed(), Op::Add, Expression::Number(1.0).boxed(), Position::default()),
+                    Expression::String(" messages".into()),
+                ])
+            }
+        ]);
+
+        let errors = Parser::new(Lexer::new("let a = 1\nprintln(\"{a b}\")\nlet c = \"{a +}\"")).parse().unwrap_err();
+
+        assert_eq!(errors.iter().map(|e| (e.line, e.span, e.to_string())).collect::<Vec<(usize, Span, String)>>(), vec![
+            (2, (13, 14), String::from("unexpected token `Identifier(\"b\")`")),
+            (3, (14, 14), String::from("unexpected end of file")),
+        ]);
+    }
+
     #[test]
     fn let_statements() {
         assert_eq!(parse("let name = 1"), vec![
             Statement::Let {
-                identifier: String::from("name"),
-                r#type: Some(Type::Number),
+                pattern: Pattern::Binding(String::from("name")),
+                r#type: None,
                 initial: Expression::Number(1.0),
+                constant: false,
             },
         ]);
 
         assert_eq!(parse("let name: number = 1"), vec![
             Statement::Let {
-                identifier: String::from("name"),
+                pattern: Pattern::Binding(String::from("name")),
                 r#type: Some(Type::Number),
                 initial: Expression::Number(1.0),
+                constant: false,
+            },
+        ]);
+
+        assert_eq!(parse(r#"let [a, [b, _], ...rest] = items
+let {"name": n, "tags": [first, ...]} = person"#), vec![
+            Statement::Let {
+                pattern: Pattern::Array(vec![
+                    Pattern::Binding("a".into()),
+                    Pattern::Array(vec![Pattern::Binding("b".into()), Pattern::Wildcard]),
+                    Pattern::Rest(Some("rest".into())),
+                ]),
+                r#type: None,
+                initial: Expression::Identifier("items".into()),
+                constant: false,
+            },
+            Statement::Let {
+                pattern: Pattern::Map(vec![
+                    ("name".into(), Pattern::Binding("n".into())),
+                    ("tags".into(), Pattern::Array(vec![Pattern::Binding("first".into()), Pattern::Rest(None)])),
+                ]),
+                r#type: None,
+                initial: Expression::Identifier("person".into()),
+                constant: false,
+            },
+        ]);
+
+        assert_eq!(parse("const limit: number = 10"), vec![
+            Statement::Let {
+                pattern: Pattern::Binding(String::from("limit")),
+                r#type: Some(Type::Number),
+                initial: Expression::Number(10.0),
+                constant: true,
             },
         ]);
+
+        let errors = Parser::new(Lexer::new("let [...rest, last] = items")).parse().unwrap_err();
+        assert_eq!(errors[0].to_string(), "unexpected token `Identifier(\"last\")`, expected `RightBracket`");
     }
 
     #[test]
@@ -770,12 +1496,39 @@ mod tests {
                 ],
                 return_type: Some(Type::String),
                 body: vec![
-                    Statement::Let { identifier: String::from("name"), r#type: Some(Type::String), initial: Expression::String("testing".into()) },
+                    Statement::Let { pattern: Pattern::Binding(String::from("name")), r#type: None, initial: Expression::String("testing".into()), constant: false }.into(),
                 ],
             }
         ]);
     }
 
+    #[test]
+    fn nested_fn_statements() {
+        let helper = || Statement::Function {
+            identifier: String::from("helper"),
+            parameters: Vec::new(),
+            return_type: None,
+            body: Vec::new(),
+        };
+
+        assert_eq!(parse("fn outer() {\n    fn helper() {}\n}"), vec![
+            Statement::Function {
+                identifier: String::from("outer"),
+                parameters: Vec::new(),
+                return_type: None,
+                body: vec![helper().into()],
+            }
+        ]);
+
+        assert_eq!(parse("if true {\n    fn helper() {}\n}"), vec![
+            Statement::If {
+                condition: Expression::Bool(true),
+                then: vec![helper().into()],
+                otherwise: Vec::new(),
+            }
+        ]);
+    }
+
     #[test]
     fn returns() {
         assert_eq!(parse("return true"), vec![
@@ -811,7 +1564,7 @@ mod tests {
             Statement::If {
                 condition: Expression::Bool(true),
                 then: vec![
-                    Statement::Let { identifier: String::from("age"), r#type: Some(Type::Number), initial: Expression::Number(1.0) }
+                    Statement::Let { pattern: Pattern::Binding(String::from("age")), r#type: None, initial: Expression::Number(1.0), constant: false }.into()
                 ],
                 otherwise: vec![],
             }
@@ -828,21 +1581,46 @@ mod tests {
                 condition: Expression::Bool(true),
                 then: vec![],
                 otherwise: vec![
-                    Statement::Let { identifier: String::from("age"), r#type: Some(Type::Number), initial: Expression::Number(1.0) }
+                    Statement::Let { pattern: Pattern::Binding(String::from("age")), r#type: None, initial: Expression::Number(1.0), constant: false }.into()
                 ],
             }
         ]);
     }
 
+    #[test]
+    fn if_expressions() {
+        let id = |i: &str| Expression::Identifier(i.to_owned());
+
+        assert_eq!(parse("let x = if a > b { a } else if a == b { 0 } else { b }"), vec![
+            Statement::Let {
+                pattern: Pattern::Binding(String::from("x")),
+                r#type: None,
+                initial: Expression::If {
+                    condition: Expression::Infix(id("a").boxed(), Op::GreaterThan, id("b").boxed(), Position::default()).boxed(),
+                    then: vec![Statement::Expression { expression: id("a") }.into()],
+                    otherwise: vec![
+                        Statement::If {
+                            condition: Expression::Infix(id("a").boxed(), Op::Equals, id("b").boxed(), Position::default()),
+                            then: vec![Statement::Expression { expression: Expression::Number(0.0) }.into()],
+                            otherwise: vec![Statement::Expression { expression: id("b") }.into()],
+                        }.into()
+                    ],
+                    position: Position::default(),
+                },
+                constant: false,
+            }
+        ]);
+    }
+
     #[test]
     fn while_statements() {
         assert_eq!(parse("while true {}"), vec![
-            Statement::While { condition: Expression::Bool(true), then: vec![] }
+            Statement::While { label: None, condition: Expression::Bool(true), then: vec![] }
         ]);
 
         assert_eq!(parse("while true { 1 }"), vec![
-            Statement::While { condition: Expression::Bool(true), then: vec![
-                Statement::Expression { expression: Expression::Number(1.0) },
+            Statement::While { label: None, condition: Expression::Bool(true), then: vec![
+                Statement::Expression { expression: Expression::Number(1.0) }.into(),
             ] }
         ]);
 
@@ -851,20 +1629,182 @@ mod tests {
                 break
             }
         "), vec![
-            Statement::While { condition: Expression::Bool(true), then: vec![
-                Statement::Break,
+            Statement::While { label: None, condition: Expression::Bool(true), then: vec![
+                Statement::Break { label: None }.into(),
             ] }
         ]);
 
         assert_eq!(parse("
             while true {
                 continue
-            }`
+            }
         "), vec![
-            Statement::While { condition: Expression::Bool(true), then: vec![
-                Statement::Continue,
+            Statement::While { label: None, condition: Expression::Bool(true), then: vec![
+                Statement::Continue { label: None }.into(),
             ] }
-        ])
+        ]);
+
+        assert_eq!(parse("
+            while true {
+                while false {}
+                break
+            }
+        "), vec![
+            Statement::While { label: None, condition: Expression::Bool(true), then: vec![
+                Statement::While { label: None, condition: Expression::Bool(false), then: vec![] }.into(),
+                Statement::Break { label: None }.into(),
+            ] }
+        ]);
+    }
+
+    #[test]
+    fn for_statements() {
+        assert_eq!(parse("for item in items {}"), vec![
+            Statement::For { label: None, key: None, value: Pattern::Binding("item".into()), iterable: Expression::Identifier("items".into()), then: vec![] }
+        ]);
+
+        assert_eq!(parse("for [a, b] in pairs {}"), vec![
+            Statement::For {
+                label: None,
+                key: None,
+                value: Pattern::Array(vec![Pattern::Binding("a".into()), Pattern::Binding("b".into())]),
+                iterable: Expression::Identifier("pairs".into()),
+                then: vec![],
+            }
+        ]);
+
+        assert_eq!(parse("for key, value in map { continue }"), vec![
+            Statement::For { label: None, key: Some("key".into()), value: Pattern::Binding("value".into()), iterable: Expression::Identifier("map".into()), then: vec![
+                Statement::Continue { label: None }.into(),
+            ] }
+        ]);
+
+        assert_eq!(parse("'outer: for i in 0..n + 1 {\n    break 'outer\n}"), vec![
+            Statement::For {
+                label: Some("outer".into()),
+                key: None,
+                value: Pattern::Binding("i".into()),
+                iterable: Expression::Range {
+                    start: Some(Expression::Number(0.0).boxed()),
+                    end: Some(Expression::Infix(Expression::Identifier("n".into()).boxed(), Op::Add, Expression::Number(1.0).boxed(), Position::default()).boxed()),
+                    inclusive: false,
+                    step: None,
+                },
+                then: vec![
+                    Statement::Break { label: Some("outer".into()) }.into(),
+                ],
+            }
+        ]);
+    }
+
+    #[test]
+    fn ranges() {
+        let range = |start: Option<f64>, end: Option<f64>, inclusive, step: Option<f64>| Expression::Range {
+            start: start.map(|n| Expression::Number(n).boxed()),
+            end: end.map(|n| Expression::Number(n).boxed()),
+            inclusive,
+            step: step.map(|n| Expression::Number(n).boxed()),
+        };
+
+        assert_eq!(parse("0..10"), vec![Statement::Expression { expression: range(Some(0.0), Some(10.0), false, None) }]);
+        assert_eq!(parse("0..=10"), vec![Statement::Expression { expression: range(Some(0.0), Some(10.0), true, None) }]);
+        assert_eq!(parse("0..10 step 2"), vec![Statement::Expression { expression: range(Some(0.0), Some(10.0), false, Some(2.0)) }]);
+        assert_eq!(parse("0.. step 2"), vec![Statement::Expression { expression: range(Some(0.0), None, false, Some(2.0)) }]);
+        assert_eq!(parse(".."), vec![Statement::Expression { expression: range(None, None, false, None) }]);
+
+        assert_eq!(parse("items[..5]"), vec![Statement::Expression {
+            expression: Expression::Index(Expression::Identifier("items".into()).boxed(), Some(range(None, Some(5.0), false, None).boxed())),
+        }]);
+
+        assert_eq!(parse("items[1..]"), vec![Statement::Expression {
+            expression: Expression::Index(Expression::Identifier("items".into()).boxed(), Some(range(Some(1.0), None, false, None).boxed())),
+        }]);
+
+        assert_eq!(parse("let r = 0..\nprintln(r)"), vec![
+            Statement::Let { pattern: Pattern::Binding("r".into()), r#type: None, initial: range(Some(0.0), None, false, None), constant: false },
+            Statement::Expression { expression: Expression::Call(Expression::Identifier("println".into()).boxed(), vec![Expression::Identifier("r".into())]) },
+        ]);
+
+        let step = || Expression::Range { start: Some(Expression::Number(0.0).boxed()), end: Some(Expression::Identifier("step".into()).boxed()), inclusive: false, step: None };
+
+        assert_eq!(parse("0..step"), vec![Statement::Expression { expression: step() }]);
+        assert_eq!(parse("items[0..step]"), vec![Statement::Expression {
+            expression: Expression::Index(Expression::Identifier("items".into()).boxed(), Some(step().boxed())),
+        }]);
+
+        let errors = Parser::new(Lexer::new("items[1..=]")).parse().unwrap_err();
+        assert_eq!(errors[0].to_string(), "unexpected token `RightBracket`");
+    }
+
+    #[test]
+    fn matches() {
+        let value = |n: f64| vec![Node::from(Statement::Expression { expression: Expression::Number(n) })];
+
+        assert_eq!(parse(r#"
+            match value {
+                1 | -2 => 1,
+                "x" => 2,
+                [a, _] if a > 0 => 3,
+                {"k": v} => {
+                    4
+                }
+                _ => 5,
+            }
+        "#), vec![
+            Statement::Expression {
+                expression: Expression::Match(Expression::Identifier("value".into()).boxed(), vec![
+                    Arm { pattern: Pattern::Or(vec![Pattern::Number(1.0), Pattern::Number(-2.0)]), guard: None, body: value(1.0) },
+                    Arm { pattern: Pattern::String("x".into()), guard: None, body: value(2.0) },
+                    Arm {
+                        pattern: Pattern::Array(vec![Pattern::Binding("a".into()), Pattern::Wildcard]),
+                        guard: Some(Expression::Infix(Expression::Identifier("a".into()).boxed(), Op::GreaterThan, Expression::Number(0.0).boxed(), Position::default())),
+                        body: value(3.0),
+                    },
+                    Arm { pattern: Pattern::Map(vec![("k".into(), Pattern::Binding("v".into()))]), guard: None, body: value(4.0) },
+                    Arm { pattern: Pattern::Wildcard, guard: None, body: value(5.0) },
+                ], Position::default()),
+            }
+        ]);
+
+        let errors = Parser::new(Lexer::new("match x { + => 1 }")).parse().unwrap_err();
+        assert_eq!(errors[0].to_string(), "unexpected token `Plus`");
+
+        let errors = Parser::new(Lexer::new("match x {\n    1 => 1\n    _ => 2\n}")).parse().unwrap_err();
+        assert_eq!(errors[0].to_string(), "unexpected token `Identifier(\"_\")`, expected `Comma`");
+    }
+
+    #[test]
+    fn labelled_loops() {
+        assert_eq!(parse("
+            'outer: while true {
+                while false {
+                    continue 'outer
+                }
+
+                break 'outer
+            }
+        "), vec![
+            Statement::While { label: Some(String::from("outer")), condition: Expression::Bool(true), then: vec![
+                Statement::While { label: None, condition: Expression::Bool(false), then: vec![
+                    Statement::Continue { label: Some(String::from("outer")) }.into(),
+                ] }.into(),
+                Statement::Break { label: Some(String::from("outer")) }.into(),
+            ] }
+        ]);
+
+        let errors = Parser::new(Lexer::new("
+            'outer: while true {
+                break 'inner
+                'outer: while true {}
+            }
+            while true {
+                let f = fn () { break }
+            }
+        ")).parse().unwrap_err();
+
+        assert!(matches!(errors[0].err, ParserErrorType::UndefinedLabel(_)));
+        assert!(matches!(errors[1].err, ParserErrorType::DuplicateLabel(_)));
+        assert!(matches!(errors[2].err, ParserErrorType::InvalidBreakableScope));
     }
 
     #[test]
@@ -874,22 +1814,65 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn expression_positions() {
+        let program = parse("let a = 1\nf(b = 2 + 3,\n    c = [\n])");
+
+        let Statement::Expression { expression: Expression::Call(_, arguments) } = &program[1].statement else { unreachable!() };
+        let Expression::Assign(_, value, position) = &arguments[0] else { unreachable!() };
+        let Expression::Infix(_, _, _, infix) = value.as_ref() else { unreachable!() };
+        let Expression::Assign(_, _, multiline) = &arguments[1] else { unreachable!() };
+
+        assert_eq!((position.line, position.span), (2, (3, 12)));
+        assert_eq!((infix.line, infix.span), (2, (7, 12)));
+        // Like a statement, an expression that runs over more than one line only points at where it starts.
+        assert_eq!((multiline.line, multiline.span), (3, (5, 6)));
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(Op::try_from(&TokenKind::DoubleQuestionEquals), Ok(Op::CoalesceAssign));
+        assert_eq!(Op::try_from(TokenKind::Minus), Ok(Op::Subtract));
+        assert_eq!(Op::try_from(&TokenKind::Comma), Err(TokenKind::Comma));
+    }
+
+    #[test]
+    fn powers() {
+        let number = |n: f64| Expression::Number(n).boxed();
+
+        assert_eq!(parse("2 ** 3 ** 2"), vec![
+            Statement::Expression { expression: Expression::Infix(number(2.0), Op::Power, Expression::Infix(number(3.0), Op::Power, number(2.0), Position::default()).boxed(), Position::default()) },
+        ]);
+
+        assert_eq!(parse("-2 ** 2"), vec![
+            Statement::Expression { expression: Expression::Prefix(Op::Subtract, Expression::Infix(number(2.0), Op::Power, number(2.0), Position::default()).boxed()) },
+        ]);
+
+        assert_eq!(parse("2 * 3 ** 2"), vec![
+            Statement::Expression { expression: Expression::Infix(number(2.0), Op::Multiply, Expression::Infix(number(3.0), Op::Power, number(2.0), Position::default()).boxed(), Position::default()) },
+        ]);
+
+        assert_eq!(parse("a **= 2"), vec![
+            Statement::Expression { expression: Expression::Infix(Expression::Identifier("a".to_owned()).boxed(), Op::PowerAssign, number(2.0), Position::default()) },
+        ]);
+    }
+
     #[test]
     fn infixes() {
         assert_eq!(parse("1 + 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Add, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Add, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 - 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Subtract, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Subtract, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 * 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Multiply, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Multiply, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 / 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Divide, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Divide, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 + 2 * 3"), vec![
@@ -898,8 +1881,10 @@ mod tests {
                     Expression::Number(1.0).boxed(), Op::Add, Expression::Infix(
                         Expression::Number(2.0).boxed(),
                         Op::Multiply,
-                        Expression::Number(3.0).boxed()
-                    ).boxed()
+                        Expression::Number(3.0).boxed(),
+                        Position::default()
+                    ).boxed(),
+                    Position::default()
                 )
             }
         ]);
@@ -911,44 +1896,81 @@ mod tests {
                         Expression::Infix(
                             Expression::Number(2.0).boxed(),
                             Op::Multiply,
-                            Expression::Number(3.0).boxed()
+                            Expression::Number(3.0).boxed(),
+                            Position::default()
                         ).boxed(),
                         Op::Divide,
-                        Expression::Number(4.0).boxed()
-                    ).boxed()
+                        Expression::Number(4.0).boxed(),
+                        Position::default()
+                    ).boxed(),
+                    Position::default()
                 )
             }
         ]);
 
         assert_eq!(parse("1 > 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::GreaterThan, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::GreaterThan, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 < 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::LessThan, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::LessThan, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 >= 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::GreaterThanEquals, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::GreaterThanEquals, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 <= 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::LessThanEquals, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::LessThanEquals, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 == 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Equals, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::Equals, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
 
         assert_eq!(parse("1 != 1"), vec![
-            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::NotEquals, Expression::Number(1.0).boxed()) }
+            Statement::Expression { expression: Expression::Infix(Expression::Number(1.0).boxed(), Op::NotEquals, Expression::Number(1.0).boxed(), Position::default()) }
         ]);
         
         assert_eq!(parse("foo = 2"), vec![
             Statement::Expression {
                 expression: Expression::Assign(
                     Expression::Identifier("foo".to_owned()).boxed(),
-                    Expression::Number(2.0).boxed()
+                    Expression::Number(2.0).boxed(),
+                    Position::default()
+                )
+            }
+        ]);
+    }
+
+    #[test]
+    fn optional_chaining() {
+        let id = |i: &str| Expression::Identifier(i.to_owned());
+
+        assert_eq!(parse("a?.b?.[0] ?? null"), vec![
+            Statement::Expression {
+                expression: Expression::Infix(
+                    // Like `.`, the property after `?.` takes any postfixes that follow it.
+                    Expression::OptionalDot(id("a").boxed(), Expression::OptionalIndex(id("b").boxed(), Expression::Number(0.0).boxed()).boxed()).boxed(),
+                    Op::Coalesce,
+                    Expression::Null.boxed(),
+                    Position::default(),
+                )
+            }
+        ]);
+
+        assert_eq!(parse("a ??= b || c ?? d"), vec![
+            Statement::Expression {
+                expression: Expression::Infix(
+                    id("a").boxed(),
+                    Op::CoalesceAssign,
+                    Expression::Infix(
+                        Expression::Infix(id("b").boxed(), Op::Or, id("c").boxed(), Position::default()).boxed(),
+                        Op::Coalesce,
+                        id("d").boxed(),
+                        Position::default(),
+                    ).boxed(),
+                    Position::default(),
                 )
             }
         ]);
@@ -971,6 +1993,7 @@ mod tests {
                     Expression::Call(Expression::Identifier("foo".to_owned()).boxed(), Vec::new()).boxed(),
                     Op::Add,
                     Expression::Call(Expression::Identifier("foo".to_owned()).boxed(), Vec::new()).boxed(),
+                    Position::default(),
                 )
             }
         ]);
@@ -1013,6 +2036,117 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn comments() {
+        assert_eq!(parse(r##"
+            /// The answer.
+            let answer = 42 // to everything
+            /* let question = */
+        "##), vec![
+            Statement::Let { pattern: Pattern::Binding(String::from("answer")), r#type: None, initial: Expression::Number(42.0), constant: false },
+        ]);
+    }
+
+    #[test]
+    fn errors() {
+        let errors = Parser::new(Lexer::new("let a = 1\nlet b = )")).parse().unwrap_err();
+
+        assert_eq!((errors[0].line, errors[0].span), (2, (9, 10)));
+        assert!(matches!(errors[0].err, ParserErrorType::UnexpectedToken(..)));
+
+        let errors = Parser::new(Lexer::new("fn main() {\n    println(1)\n")).parse().unwrap_err();
+
+        assert_eq!(errors[0].to_string(), "unexpected end of file");
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let errors = Parser::new(Lexer::new("let a = 1.2.3\nlet b = 1 # 2\nlet c = \"\\q\"")).parse().unwrap_err();
+
+        assert_eq!(errors.iter().map(|e| (e.line, e.to_string())).collect::<Vec<(usize, String)>>(), vec![
+            (1, String::from("malformed number `1.2.3`")),
+            (2, String::from("unexpected character `#`")),
+            (3, String::from("unknown escape sequence `\\q`")),
+        ]);
+    }
+
+    #[test]
+    fn recovery() {
+        let (program, errors) = Parser::new(Lexer::new(r##"
+            let a = )
+            let b = 2
+            fn main() {
+                println(b)
+                let c = ]
+                break
+            }
+            }
+            let d = 4
+        "##)).parse_partial();
+
+        assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<usize>>(), vec![2, 6, 7, 9]);
+        assert!(matches!(errors[2].err, ParserErrorType::InvalidBreakableScope));
+
+        assert_eq!(program, vec![
+            Statement::Let { pattern: Pattern::Binding(String::from("b")), r#type: None, initial: Expression::Number(2.0), constant: false },
+            Statement::Function {
+                identifier: String::from("main"),
+                parameters: vec![],
+                return_type: None,
+                body: vec![
+                    Statement::Expression {
+                        expression: Expression::Call(Expression::Identifier("println".to_owned()).boxed(), vec![
+                            Expression::Identifier("b".to_owned()),
+                        ])
+                    }.into(),
+                ],
+            },
+            Statement::Let { pattern: Pattern::Binding(String::from("d")), r#type: None, initial: Expression::Number(4.0), constant: false },
+        ]);
+    }
+
+    #[test]
+    fn types() {
+        let annotation = |source: &str| match parse(&format!("let a: {} = 1", source)).remove(0).statement {
+            Statement::Let { r#type, .. } => r#type.unwrap(),
+            _ => unreachable!(),
+        };
+
+        let boxed = |t: Type| Box::new(t);
+
+        assert_eq!(annotation("[number]"), Type::Array(boxed(Type::Number)));
+        assert_eq!(annotation("{string: [bool]}"), Type::Map(boxed(Type::String), boxed(Type::Array(boxed(Type::Bool)))));
+        assert_eq!(annotation(r#"{"x": number, "y": number?}"#), Type::Record(vec![("x".into(), Type::Number), ("y".into(), Type::Optional(boxed(Type::Number)))]));
+        assert_eq!(annotation("string | number?"), Type::Union(vec![Type::String, Type::Optional(boxed(Type::Number))]));
+        assert_eq!(annotation("(string | number)?"), Type::Optional(boxed(Type::Union(vec![Type::String, Type::Number]))));
+        assert_eq!(annotation("fn(number, string) -> bool"), Type::Function(vec![Type::Number, Type::String], Some(boxed(Type::Bool))));
+        assert_eq!(annotation("fn() | Point"), Type::Union(vec![Type::Function(vec![], None), Type::Named("Point".into())]));
+
+        assert_eq!(parse(r#"type Point = {"x": number, "y": number}"#), vec![
+            Statement::TypeAlias {
+                identifier: String::from("Point"),
+                r#type: Type::Record(vec![("x".into(), Type::Number), ("y".into(), Type::Number)]),
+            },
+        ]);
+
+        assert!(Parser::new(Lexer::new("let a: = 1")).parse().is_err());
+        assert!(Parser::new(Lexer::new("let a: {string number} = 1")).parse().is_err());
+    }
+
+    #[test]
+    fn positions() {
+        let program = parse("let a = 1 + 2\nfn main() {\n    while true {\n        break\n    }\n}");
+
+        assert_eq!((program[0].line, program[0].span), (1, (1, 14)));
+        assert_eq!((program[1].line, program[1].span), (2, (1, 3)));
+
+        let Statement::Function { body, .. } = &program[1].statement else { unreachable!() };
+        assert_eq!((body[0].line, body[0].span), (3, (5, 10)));
+
+        let Statement::While { then, .. } = &body[0].statement else { unreachable!() };
+        assert_eq!((then[0].line, then[0].span), (4, (9, 14)));
+    }
+
     fn parse(source: &str) -> Program {
         let lexer = Lexer::new(source);
 
@@ -0,0 +1,150 @@
+use crate::{Statement, Node, Expression};
+use super::Pass;
+
+/// Moves function definitions to the top of the block they're defined in, along with every block nested inside of it,
+/// so that functions can be called before they're defined.
+///
+/// `use` statements stay above everything else, and the hoist is stable: statements of the same kind keep the order
+/// they were written in.
+pub(crate) struct HoistFunctions;
+
+impl Pass for HoistFunctions {
+    fn run(&mut self, ast: &mut [Node]) {
+        hoist_functions(ast);
+    }
+}
+
+fn hoist_functions(ast: &mut [Node]) {
+    ast.sort_by_key(|node| match node.statement {
+        Statement::Use { .. } => 0,
+        Statement::Function { .. } => 1,
+        _ => 2,
+    });
+
+    for node in ast.iter_mut() {
+        match &mut node.statement {
+            Statement::Function { body, .. } => hoist_functions(body),
+            Statement::If { condition, then, otherwise } => {
+                hoist_closures(condition);
+                hoist_functions(then);
+                hoist_functions(otherwise);
+            },
+            Statement::While { condition, then, .. } | Statement::For { iterable: condition, then, .. } => {
+                hoist_closures(condition);
+                hoist_functions(then);
+            },
+            Statement::Let { initial: expression, .. } | Statement::Return { expression } | Statement::Expression { expression } => hoist_closures(expression),
+            Statement::Use { .. } | Statement::Break { .. } | Statement::Continue { .. } | Statement::TypeAlias { .. } => {},
+        }
+    }
+}
+
+/// Closures have blocks of their own, so any functions defined inside of them need hoisting too.
+fn hoist_closures(expression: &mut Expression) {
+    match expression {
+        Expression::Closure(_, body) => hoist_functions(body),
+        Expression::Array(items) | Expression::Interpolated(items) => items.iter_mut().for_each(hoist_closures),
+        Expression::Map(members) => members.values_mut().for_each(hoist_closures),
+        Expression::Prefix(_, value) => hoist_closures(value),
+        Expression::Infix(left, _, right, _) | Expression::Assign(left, right, _) | Expression::Dot(left, right)
+            | Expression::OptionalIndex(left, right) | Expression::OptionalDot(left, right) => {
+            hoist_closures(left);
+            hoist_closures(right);
+        },
+        Expression::Call(callable, arguments) => {
+            hoist_closures(callable);
+            arguments.iter_mut().for_each(hoist_closures);
+        },
+        Expression::Range { start, end, step, .. } => {
+            for bound in [start, end, step].into_iter().flatten() {
+                hoist_closures(bound);
+            }
+        },
+        Expression::If { condition, then, otherwise, .. } => {
+            hoist_closures(condition);
+            hoist_functions(then);
+            hoist_functions(otherwise);
+        },
+        Expression::Match(subject, arms, _) => {
+            hoist_closures(subject);
+
+            for arm in arms {
+                if let Some(guard) = &mut arm.guard {
+                    hoist_closures(guard);
+                }
+
+                hoist_functions(&mut arm.body);
+            }
+        },
+        Expression::Index(target, index) => {
+            hoist_closures(target);
+
+            if let Some(index) = index {
+                hoist_closures(index);
+            }
+        },
+        Expression::String(_) | Expression::Number(_) | Expression::BigInt(_) | Expression::Bool(_) | Expression::Null | Expression::Identifier(_) => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, Lexer};
+
+    #[test]
+    fn functions_are_hoisted_above_other_statements() {
+        assert_eq!(hoisted("let a = 1\nfn b() {}\nlet c = 2\nfn d() {}"), vec!["fn b", "fn d", "let a", "let c"]);
+    }
+
+    #[test]
+    fn use_statements_stay_first() {
+        assert_eq!(hoisted("use b from \"a\"\nlet c = 1\nfn d() {}\nuse f from \"e\""), vec!["use a", "use e", "fn d", "let c"]);
+    }
+
+    #[test]
+    fn order_is_stable() {
+        // Enough statements that the standard library's sorts stop using insertion sort.
+        let name = |i: u8| format!("{}{}", (b'a' + i / 26) as char, (b'a' + i % 26) as char);
+        let source = (0..60).map(|i| if i % 3 == 0 { format!("fn {}() {{}}", name(i)) } else { format!("let {} = {}", name(i), i) }).collect::<Vec<String>>().join("\n");
+
+        let mut expected = (0..60).filter(|i| i % 3 == 0).map(|i| format!("fn {}", name(i))).collect::<Vec<String>>();
+        expected.extend((0..60).filter(|i| i % 3 != 0).map(|i| format!("let {}", name(i))));
+
+        assert_eq!(hoisted(&source), expected);
+    }
+
+    #[test]
+    fn functions_are_hoisted_per_block() {
+        let program = run("let a = 1\nfn outer() {\n    let b = inner()\n    fn inner() {}\n}\nlet c = fn () {\n    let d = 1\n    fn nested() {}\n}");
+
+        let Statement::Function { body, .. } = &program[0].statement else { unreachable!() };
+        assert_eq!(names(body), vec!["fn inner", "let b"]);
+        assert_eq!(body[1].line, 3);
+
+        let Statement::Let { initial: Expression::Closure(_, body), .. } = &program[2].statement else { unreachable!() };
+        assert_eq!(names(body), vec!["fn nested", "let d"]);
+    }
+
+    /// Parse the source code without running any passes, then run the hoist on its own.
+    fn run(source: &str) -> Vec<Node> {
+        let (mut ast, errors) = Parser::new(Lexer::new(source)).parse_partial();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        HoistFunctions.run(&mut ast);
+        ast
+    }
+
+    fn hoisted(source: &str) -> Vec<String> {
+        names(&run(source))
+    }
+
+    fn names(nodes: &[Node]) -> Vec<String> {
+        nodes.iter().map(|node| match &node.statement {
+            Statement::Use { module, .. } => format!("use {}", module),
+            Statement::Function { identifier, .. } => format!("fn {}", identifier),
+            Statement::Let { pattern, .. } => format!("let {}", pattern.bindings().join(", ")),
+            statement => format!("{:?}", statement),
+        }).collect()
+    }
+}
\ No newline at end of file
@@ -1,14 +1,21 @@
-use crate::Statement;
-use std::cmp::Ordering;
+use crate::Node;
 
-pub fn pass(ast: &mut Vec<Statement>) {
-    hoist_functions(ast);
+mod hoist;
+
+use hoist::HoistFunctions;
+
+/// A `Pass` transforms the program after it has been parsed, but before it's handed to the type checker and compiler.
+pub(crate) trait Pass {
+    fn run(&mut self, ast: &mut [Node]);
 }
 
-fn hoist_functions(ast: &mut Vec<Statement>) {
-    ast.sort_unstable_by(|a, _| if matches!(a, Statement::Function { .. }) {
-        Ordering::Less
-    } else {
-        Ordering::Equal
-    });
+/// Run every pass over the program, in order.
+pub fn pass(ast: &mut [Node]) {
+    let passes: Vec<Box<dyn Pass>> = vec![
+        Box::new(HoistFunctions),
+    ];
+
+    for mut pass in passes {
+        pass.run(ast);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use crate::{Expression, Node};
+
+/// The `Pattern` enumeration represents the left-hand side of a `match` arm, as well as the names that `let`, loops
+/// and parameters destructure a value into.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Pattern {
+    /// `_`, which matches anything.
+    Wildcard,
+    /// `name`, which matches anything and binds it to `name`.
+    Binding(String),
+    String(String),
+    Number(f64),
+    BigInt(String),
+    Bool(bool),
+    Null,
+    /// `[a, b]`, which matches an array with exactly as many items.
+    Array(Vec<Pattern>),
+    /// `...rest`, which can only come last in an array pattern. It matches any number of the remaining items, and binds
+    /// them to a new array unless it's written as `...` or `..._`.
+    Rest(Option<String>),
+    /// `{"k": v}`, which matches a map that has all of the keys, ignoring any others.
+    Map(Vec<(String, Pattern)>),
+    /// `1 | 2`
+    Or(Vec<Pattern>),
+}
+
+impl Pattern {
+    /// Whether the pattern matches every value.
+    pub fn irrefutable(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Binding(_) => true,
+            Pattern::Or(patterns) => patterns.iter().any(Pattern::irrefutable),
+            _ => false,
+        }
+    }
+
+    /// The names that the pattern binds, in the order they're written.
+    pub fn bindings(&self) -> Vec<&str> {
+        match self {
+            Pattern::Binding(name) | Pattern::Rest(Some(name)) => vec![name.as_str()],
+            Pattern::Array(items) | Pattern::Or(items) => items.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Map(members) => members.iter().flat_map(|(_, pattern)| pattern.bindings()).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A single `pattern if guard => body` arm of a `match` expression.
+#[derive(Debug, PartialEq)]
+pub struct Arm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Vec<Node>,
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::{Expression, Type};
+use crate::{Expression, Pattern, Type, Span};
 
 /// The main type of `Node` in Tonic. Every line in the source code will eventually be parsed into
 /// a `Statement`, including arbitrary expressions.
@@ -7,25 +7,38 @@ use crate::{Expression, Type};
 /// information about the position of the node, that is the responsibility of `Node`.
 #[derive(Debug, PartialEq)]
 pub enum Statement {
+    /// `let name = value`, or `let [a, b] = value` to destructure the value into several names. A `const` is written
+    /// the same way, but the names that it binds can't be reassigned.
     Let {
-        identifier: String,
+        pattern: Pattern,
         r#type: Option<Type>,
         initial: Expression,
+        constant: bool,
     },
     Function {
         identifier: String,
         parameters: Vec<Parameter>,
         return_type: Option<Type>,
-        body: Vec<Statement>,
+        body: Vec<Node>,
     },
     If {
         condition: Expression,
-        then: Vec<Statement>,
-        otherwise: Vec<Statement>,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
     },
     While {
+        label: Option<String>,
         condition: Expression,
-        then: Vec<Statement>,
+        then: Vec<Node>,
+    },
+    /// `for value in iterable {}`, or `for key, value in iterable {}` to loop over the entries of a map. The value can be
+    /// destructured, such as `for [a, b] in pairs {}`.
+    For {
+        label: Option<String>,
+        key: Option<String>,
+        value: Pattern,
+        iterable: Expression,
+        then: Vec<Node>,
     },
     Return {
         expression: Expression,
@@ -37,21 +50,79 @@ pub enum Statement {
         module: String,
         imports: Vec<String>,
     },
-    Break,
-    Continue,
+    Break {
+        label: Option<String>,
+    },
+    Continue {
+        label: Option<String>,
+    },
+    TypeAlias {
+        identifier: String,
+        r#type: Type,
+    },
+}
+
+/// A `Node` holds a `Statement` along with its position in the source code.
+/// 
+/// The `line` and `span` follow the same conventions as a `Token`. A statement that spans multiple lines
+/// only has the span of its first line. A `Node` that was created by hand, rather than by the parser,
+/// has a `line` of `0`.
+/// 
+/// Positions are metadata, so two nodes are equal whenever their statements are.
+#[derive(Debug)]
+pub struct Node {
+    pub statement: Statement,
+    pub line: usize,
+    pub span: Span,
+}
+
+impl Node {
+    pub fn new(statement: Statement, line: usize, span: Span) -> Self {
+        Self { statement, line, span }
+    }
+}
+
+impl From<Statement> for Node {
+    fn from(statement: Statement) -> Self {
+        Self::new(statement, 0, (0, 0))
+    }
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.statement == other.statement
+    }
+}
+
+impl PartialEq<Statement> for Node {
+    fn eq(&self, other: &Statement) -> bool {
+        &self.statement == other
+    }
 }
 
 /// The `Parameter` struct is used to represent a function parameter.
 /// 
-/// It stores information about the name of the parameter and the expected type of the parameter.
+/// It stores information about the name of the parameter, or the pattern that destructures it, and the expected type of the parameter.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Parameter {
-    pub name: String,
+    pub pattern: Pattern,
     pub r#type: Option<Type>,
 }
 
 impl Parameter {
     pub fn new(name: impl Into<String>, r#type: Option<Type>) -> Self {
-        Self { name: name.into(), r#type }
+        Self::with_pattern(Pattern::Binding(name.into()), r#type)
+    }
+
+    pub fn with_pattern(pattern: Pattern, r#type: Option<Type>) -> Self {
+        Self { pattern, r#type }
+    }
+
+    /// The name of the parameter, or `None` if it's destructured.
+    pub fn name(&self) -> Option<&str> {
+        match &self.pattern {
+            Pattern::Binding(name) => Some(name),
+            _ => None,
+        }
     }
 }
\ No newline at end of file
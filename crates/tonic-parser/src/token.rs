@@ -1,6 +1,42 @@
+use std::fmt::{Display, Formatter, Result};
+
 /// A type-alias that represents the start and end point of a token.
 pub type Span = (usize, usize);
 
+/// Describes why the lexer couldn't produce a valid token from the source code.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexError {
+    UnexpectedCharacter(char),
+    MalformedNumber(String),
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidEscape(char),
+    /// A `\x` or `\u{...}` escape that doesn't have the right digits, or isn't a valid character.
+    MalformedEscape(String),
+    /// A `{` inside of a string that isn't closed with a `}` on the same line.
+    UnclosedInterpolation,
+    /// A `{}` inside of a string that doesn't have an expression between the braces.
+    EmptyInterpolation,
+    /// A `}` inside of a string that doesn't close a `{`.
+    UnmatchedBrace,
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::UnexpectedCharacter(c) => write!(f, "unexpected character `{}`", c),
+            Self::MalformedNumber(n) => write!(f, "malformed number `{}`", n),
+            Self::UnterminatedString => write!(f, "unterminated string"),
+            Self::UnterminatedComment => write!(f, "unterminated block comment"),
+            Self::InvalidEscape(c) => write!(f, "unknown escape sequence `\\{}`", c),
+            Self::MalformedEscape(escape) => write!(f, "malformed escape sequence `{}`", escape),
+            Self::UnclosedInterpolation => write!(f, "unclosed `{{` in string, use `\\{{` to write a brace"),
+            Self::EmptyInterpolation => write!(f, "expected an expression between `{{}}` in string, use `\\{{` to write a brace"),
+            Self::UnmatchedBrace => write!(f, "unmatched `}}` in string, use `\\}}` to write a brace"),
+        }
+    }
+}
+
 /// Represents the "kind" of a token.
 /// 
 /// They are separated into groups in the source code, where each group signifies a sub-type of token.
@@ -8,28 +44,56 @@ pub type Span = (usize, usize);
 pub enum TokenKind {
     Fn,
     Let,
+    Const,
     If,
     Else,
     While,
+    For,
+    In,
     Return,
     Break,
     Continue,
     Use,
     From,
+    Type,
+    Match,
 
     True,
     False,
+    Null,
 
     Identifier(String),
     String(String),
+    /// A string with expressions embedded in it, such as `"Hello, {name}!"`.
+    Interpolated(Vec<Fragment>),
     Number(f64),
+    /// The digits of a `BigInt` literal such as `10n`, without the `n` or any `_` separators.
+    BigInt(String),
+    Label(String),
+
+    /// A `///` doc comment. The parser skips over these, but they're kept as tokens (with their spans)
+    /// so that tooling such as formatters and documentation generators can read them.
+    DocComment(String),
+
+    /// Produced when the lexer finds something that isn't valid Tonic. The parser turns these into a `ParserError`.
+    Error(LexError),
 
     Colon,
     DoubleColon,
     SemiColon,
     Comma,
     Dot,
+    /// `?.`, which stops at a `null` or missing value instead of reading a property from it.
+    QuestionDot,
+    DoubleDot,
+    DoubleDotEquals,
+    Ellipsis,
     Arrow,
+    FatArrow,
+    Pipe,
+    Question,
+    DoubleQuestion,
+    DoubleQuestionEquals,
 
     LeftParen,
     RightParen,
@@ -53,6 +117,7 @@ pub enum TokenKind {
     MinusEquals,
     AsteriskEquals,
     SlashEquals,
+    DoubleAsteriskEquals,
     EqualsEquals,
     NotEquals,
     GreaterThan,
@@ -64,10 +129,17 @@ pub enum TokenKind {
     Eof,
 }
 
+/// A piece of an interpolated string: either some literal text, or the tokens of an embedded expression.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Fragment {
+    Literal(String),
+    Code(Vec<Token>),
+}
+
 /// Stores information regarding a token.
 /// 
 /// The `Token` type holds information about the type of a token (`TokenType`), as well as it's `line` and `span` (start and end column) in the source code.
-#[derive(Debug, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub kind: TokenKind,
     pub line: usize,
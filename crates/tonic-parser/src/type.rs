@@ -1,24 +1,91 @@
+use std::fmt::{Display, Formatter, Result};
+
 // The `Type` enumeration is the single-source of truth for type strings in Tonic.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Type {
     String,
     Bool,
     Number,
+    BigInt,
+    /// `[number]`
+    Array(Box<Type>),
+    /// `{string: number}`
+    Map(Box<Type>, Box<Type>),
+    /// `{"x": number, "y": number}`, a map with a known set of keys.
+    Record(Vec<(String, Type)>),
+    /// `string?`
+    Optional(Box<Type>),
+    /// `fn(number) -> bool`. A function type without a `->` can return anything.
+    Function(Vec<Type>, Option<Box<Type>>),
+    /// `string | number`
+    Union(Vec<Type>),
+    /// The name of a type alias, such as `Point` in `type Point = {"x": number, "y": number}`.
+    Named(String),
 }
 
 impl Type {
-    /// Check whether a string is a valid type string.
+    /// Check whether a string is the name of a built-in type.
     pub fn valid(string: impl Into<String>) -> bool {
-        matches!(string.into().as_str(), "string" | "bool" | "number")
+        matches!(string.into().as_str(), "string" | "bool" | "number" | "bigint")
     }
 
-    /// Convert a type string into a valid `Type` variant.
+    /// Convert a type string into a `Type` variant. Anything that isn't a built-in type is a `Named` type.
     pub fn string(string: String) -> Self {
         match string.as_str() {
             "string" => Type::String,
             "bool" => Type::Bool,
             "number" => Type::Number,
-            _ => todo!()
+            "bigint" => Type::BigInt,
+            _ => Type::Named(string),
         }
     }
+
+    /// Whether the type needs wrapping in parentheses when it's used inside of an optional or union type.
+    fn compound(&self) -> bool {
+        matches!(self, Type::Union(_) | Type::Function(..))
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Type::String => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::Number => write!(f, "number"),
+            Type::BigInt => write!(f, "bigint"),
+            Type::Array(item) => write!(f, "[{}]", item),
+            Type::Map(key, value) => write!(f, "{{{}: {}}}", key, value),
+            Type::Record(fields) => write!(f, "{{{}}}", fields.iter().map(|(k, v)| format!("{:?}: {}", k, v)).collect::<Vec<String>>().join(", ")),
+            Type::Optional(inner) if inner.compound() => write!(f, "({})?", inner),
+            Type::Optional(inner) => write!(f, "{}?", inner),
+            Type::Function(parameters, return_type) => {
+                write!(f, "fn({})", parameters.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(", "))?;
+
+                match return_type {
+                    Some(return_type) if return_type.compound() => write!(f, " -> ({})", return_type),
+                    Some(return_type) => write!(f, " -> {}", return_type),
+                    None => Ok(()),
+                }
+            },
+            Type::Union(types) => write!(f, "{}", types.iter().map(|t| if t.compound() { format!("({})", t) } else { t.to_string() }).collect::<Vec<String>>().join(" | ")),
+            Type::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let point = Type::Record(vec![("x".into(), Type::Number), ("y".into(), Type::Number)]);
+
+        assert_eq!(Type::Array(Box::new(Type::Number)).to_string(), "[number]");
+        assert_eq!(Type::Map(Box::new(Type::String), Box::new(Type::Number)).to_string(), "{string: number}");
+        assert_eq!(point.to_string(), r#"{"x": number, "y": number}"#);
+        assert_eq!(Type::Optional(Box::new(Type::Union(vec![Type::String, Type::Number]))).to_string(), "(string | number)?");
+        assert_eq!(Type::Function(vec![Type::Number], Some(Box::new(Type::Bool))).to_string(), "fn(number) -> bool");
+        assert_eq!(Type::Union(vec![Type::Function(vec![], None), Type::Named("Point".into())]).to_string(), "(fn()) | Point");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,837 @@
+use tonic_parser::{Statement, Node, Expression, Op, Parameter, Pattern, Type, Span};
+use crate::{TypeError, TypeErrorType};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+enum Binding {
+    /// A variable or parameter, along with its annotation. Unannotated bindings are dynamically typed.
+    Value(Option<Type>),
+    /// A `const`, which is the same as a `Value` except that it can't be reassigned.
+    Constant(Option<Type>),
+    Function(Vec<Parameter>, Option<Type>),
+}
+
+/// The bindings and type aliases that are declared in a block.
+#[derive(Debug, Default)]
+struct Scope {
+    bindings: HashMap<String, Binding>,
+    aliases: HashMap<String, Type>,
+}
+
+/// Aliases can refer to themselves, so they're only expanded this many times before the checker gives up.
+const MAX_ALIAS_DEPTH: usize = 32;
+
+/// The `Checker` walks a program and compares the types it can infer against the annotations in the code.
+///
+/// Anything the checker can't be sure about, such as the result of calling an unannotated function, is treated
+/// as dynamic and never causes an error.
+#[derive(Debug)]
+pub struct Checker {
+    scopes: Vec<Scope>,
+    /// The name and return type of the function that is being checked, if any.
+    function: Option<(String, Option<Type>)>,
+    /// The position of the statement that is being checked.
+    position: (usize, Span),
+    errors: Vec<TypeError>,
+}
+
+impl Checker {
+    pub fn new() -> Self {
+        Self {
+            scopes: Vec::new(),
+            function: None,
+            position: (0, (0, 0)),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Check a program, returning the errors in the order they appear in the source code.
+    pub fn check(mut self, program: &[Node]) -> Vec<TypeError> {
+        self.block(program);
+
+        // Hoisting moves functions to the top of the program, so they aren't necessarily checked in order.
+        self.errors.sort_by_key(|e| (e.line, e.span.0));
+        self.errors
+    }
+
+    fn block(&mut self, nodes: &[Node]) {
+        self.scoped(Scope::default(), nodes);
+    }
+
+    fn scoped(&mut self, scope: Scope, nodes: &[Node]) {
+        let position = self.position;
+
+        self.scopes.push(scope);
+
+        // Functions and type aliases are hoisted, so they can be used before they're declared.
+        for node in nodes {
+            match &node.statement {
+                Statement::Function { identifier, parameters, return_type, .. } => {
+                    self.declare(identifier, Binding::Function(parameters.clone(), return_type.clone()));
+                },
+                Statement::TypeAlias { identifier, r#type } => {
+                    if let Some(scope) = self.scopes.last_mut() {
+                        scope.aliases.insert(identifier.clone(), r#type.clone());
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        // Hoisting also moves functions above the bindings they use, so their bodies are checked once the rest of the
+        // block has declared everything, such as a `const` that a function tries to reassign.
+        let (functions, statements): (Vec<&Node>, Vec<&Node>) = nodes.iter()
+            .partition(|node| matches!(node.statement, Statement::Function { .. }));
+
+        for node in statements.into_iter().chain(functions) {
+            self.position = (node.line, node.span);
+            self.statement(&node.statement);
+        }
+
+        self.scopes.pop();
+        self.position = position;
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let { pattern, r#type, initial, constant } => {
+                let found = match r#type {
+                    Some(expected) => self.infer_as(expected, initial),
+                    None => self.infer(initial),
+                };
+
+                if let Some(expected) = r#type {
+                    self.defined(expected);
+                    self.expect(expected, found, TypeErrorType::MismatchedTypes);
+                }
+
+                let binding = |r#type| if *constant { Binding::Constant(r#type) } else { Binding::Value(r#type) };
+
+                match pattern {
+                    Pattern::Binding(identifier) => self.declare(identifier, binding(r#type.clone())),
+                    pattern => {
+                        for name in pattern.bindings() {
+                            self.declare(name, binding(None));
+                        }
+                    },
+                }
+            },
+            Statement::Function { identifier, parameters, return_type, body } => {
+                if let Some(return_type) = return_type {
+                    self.defined(return_type);
+                }
+
+                let function = self.function.replace((identifier.clone(), return_type.clone()));
+
+                self.body(parameters, body);
+                self.function = function;
+            },
+            Statement::Return { expression } => match self.function.clone() {
+                Some((function, Some(expected))) => {
+                    let found = self.infer_as(&expected, expression);
+
+                    self.expect(&expected, found, |expected, found| TypeErrorType::InvalidReturn(function, expected, found));
+                },
+                _ => {
+                    self.infer(expression);
+                },
+            },
+            Statement::If { condition, then, otherwise } => {
+                self.infer(condition);
+                self.block(then);
+                self.block(otherwise);
+            },
+            Statement::While { condition, then, .. } => {
+                self.infer(condition);
+                self.block(then);
+            },
+            Statement::For { key, value, iterable, then, .. } => {
+                self.infer(iterable);
+
+                // Like an unannotated `let`, the loop's bindings are dynamic.
+                let scope = Scope {
+                    bindings: key.iter().map(|name| (name.clone(), Binding::Value(None))).chain(dynamic(value)).collect(),
+                    ..Scope::default()
+                };
+
+                self.scoped(scope, then);
+            },
+            Statement::Expression { expression } => {
+                self.infer(expression);
+            },
+            Statement::Use { imports, .. } => {
+                for import in imports {
+                    self.declare(import, Binding::Value(None));
+                }
+            },
+            Statement::TypeAlias { r#type, .. } => self.defined(r#type),
+            Statement::Break { .. } | Statement::Continue { .. } => {},
+        }
+    }
+
+    /// Infer the type of an expression, checking any calls and assignments inside of it along the way.
+    fn infer(&mut self, expression: &Expression) -> Option<Type> {
+        match expression {
+            Expression::String(_) => Some(Type::String),
+            // Any value can be embedded in a string, so the parts only need checking on their own.
+            Expression::Interpolated(parts) => {
+                for part in parts {
+                    self.infer(part);
+                }
+
+                Some(Type::String)
+            },
+            Expression::Number(_) => Some(Type::Number),
+            Expression::BigInt(_) => Some(Type::BigInt),
+            Expression::Bool(_) => Some(Type::Bool),
+            // `null` can stand in for any value, so its type is unknown.
+            Expression::Null => None,
+            Expression::Array(items) => {
+                let types = items.iter().map(|i| self.infer(i)).collect::<Option<Vec<Type>>>()?;
+
+                match types.first() {
+                    Some(first) if types.iter().all(|t| t == first) => Some(Type::Array(Box::new(first.clone()))),
+                    _ => None,
+                }
+            },
+            Expression::Map(members) => {
+                let mut fields = members.iter().map(|(k, v)| Some((k.clone(), self.infer(v)?))).collect::<Vec<Option<(String, Type)>>>();
+
+                if fields.iter().any(Option::is_none) {
+                    return None
+                }
+
+                fields.sort_by(|a, b| a.as_ref().map(|a| &a.0).cmp(&b.as_ref().map(|b| &b.0)));
+
+                Some(Type::Record(fields.into_iter().flatten().collect()))
+            },
+            Expression::Identifier(identifier) => match self.lookup(identifier)? {
+                Binding::Value(r#type) | Binding::Constant(r#type) => r#type,
+                Binding::Function(parameters, return_type) => signature(&parameters, return_type),
+            },
+            Expression::Prefix(op, value) => {
+                let value = self.infer(value).map(|t| self.resolve(&t));
+
+                match op {
+                    Op::Not => Some(Type::Bool),
+                    Op::Subtract if value == Some(Type::BigInt) => Some(Type::BigInt),
+                    Op::Subtract => Some(Type::Number),
+                    _ => None,
+                }
+            },
+            Expression::Infix(left, op, right, _) => {
+                let left_type = self.infer(left).map(|t| self.resolve(&t));
+                let right_type = self.infer(right).map(|t| self.resolve(&t));
+
+                let (op, assigns) = match op {
+                    Op::AddAssign => (&Op::Add, true),
+                    Op::SubtractAssign => (&Op::Subtract, true),
+                    Op::MultiplyAssign => (&Op::Multiply, true),
+                    Op::DivideAssign => (&Op::Divide, true),
+                    Op::PowerAssign => (&Op::Power, true),
+                    Op::CoalesceAssign => (&Op::Coalesce, true),
+                    op => (op, false),
+                };
+
+                // `??=` only ever stores the right-hand side, so that's what needs to fit the target.
+                let stored = if op == &Op::Coalesce { right_type.clone() } else { None };
+
+                // JavaScript throws when arithmetic, including `+`, mixes a `BigInt` with a `number`.
+                if let (Some(expected @ (Type::BigInt | Type::Number)), Some(found @ (Type::BigInt | Type::Number))) = (&left_type, &right_type) {
+                    if expected != found && matches!(op, Op::Add | Op::Subtract | Op::Multiply | Op::Divide | Op::Mod | Op::Power) {
+                        self.error(TypeErrorType::MismatchedTypes(expected.clone(), found.clone()));
+                    }
+                }
+
+                let r#type = infix(left_type, op, right_type);
+
+                if assigns {
+                    self.assign(left, stored.or_else(|| r#type.clone()));
+                }
+
+                r#type
+            },
+            Expression::Assign(target, value, _) => {
+                let r#type = self.infer(value);
+
+                self.assign(target, r#type.clone());
+
+                r#type
+            },
+            Expression::Call(callable, arguments) => {
+                let found = arguments.iter().map(|a| self.infer(a)).collect::<Vec<Option<Type>>>();
+
+                // Named functions know the names of their parameters, whereas function types only know their positions.
+                let (function, parameters, return_type) = match callable.as_ref() {
+                    Expression::Identifier(identifier) => match self.lookup(identifier)? {
+                        Binding::Function(parameters, return_type) => (identifier.clone(), parameters, return_type),
+                        Binding::Value(r#type) | Binding::Constant(r#type) => match r#type.map(|t| self.resolve(&t))? {
+                            Type::Function(parameters, return_type) => (identifier.clone(), positional(parameters), return_type.map(|t| *t)),
+                            _ => return None,
+                        },
+                    },
+                    callable => match self.infer(callable).map(|t| self.resolve(&t))? {
+                        Type::Function(parameters, return_type) => ("function".to_owned(), positional(parameters), return_type.map(|t| *t)),
+                        _ => return None,
+                    },
+                };
+
+                for (i, (parameter, found)) in parameters.iter().zip(found).enumerate() {
+                    if let Some(expected) = &parameter.r#type {
+                        // A destructured parameter doesn't have a name, so it's described by its position instead.
+                        let name = parameter.name().map_or_else(|| (i + 1).to_string(), str::to_owned);
+
+                        self.expect(expected, found, |expected, found| TypeErrorType::InvalidArgument(function.clone(), name, expected, found));
+                    }
+                }
+
+                return_type
+            },
+            Expression::Index(target, index) => {
+                let target = self.infer(target).map(|t| self.resolve(&t));
+                let index = index.as_ref().map(|index| (index.as_ref(), self.infer(index)));
+
+                match (target?, index?) {
+                    // Slicing an array or a string gives back another one.
+                    (r#type @ (Type::Array(_) | Type::String), (Expression::Range { .. }, _)) => Some(r#type),
+                    (Type::Array(item), _) => Some(*item),
+                    (Type::Map(_, value), _) => Some(*value),
+                    (Type::Record(fields), (Expression::String(key), _)) => field(fields, key),
+                    _ => None,
+                }
+            },
+            Expression::Dot(target, property) => {
+                let target = self.infer(target).map(|t| self.resolve(&t))?;
+
+                match (target, property.as_ref()) {
+                    (Type::Record(fields), Expression::Identifier(key)) => field(fields, key),
+                    (Type::Array(_) | Type::String, Expression::Identifier(key)) if key == "length" => Some(Type::Number),
+                    _ => None,
+                }
+            },
+            // Optional chaining gives back `null` for a missing value, so whatever it reads could be missing too.
+            Expression::OptionalIndex(target, index) => {
+                self.infer(target);
+                self.infer(index);
+
+                None
+            },
+            Expression::OptionalDot(target, _) => {
+                self.infer(target);
+
+                None
+            },
+            Expression::Closure(parameters, body) => {
+                // Closures can't annotate their return type, so whatever they return is fine.
+                let function = self.function.replace(("closure".to_owned(), None));
+
+                self.body(parameters, body);
+                self.function = function;
+
+                signature(parameters, None)
+            },
+            Expression::Match(subject, arms, _) => {
+                self.infer(subject);
+
+                // A pattern can match values of any type, so the bindings it introduces are dynamic.
+                for arm in arms {
+                    self.scopes.push(Scope {
+                        bindings: dynamic(&arm.pattern),
+                        ..Scope::default()
+                    });
+
+                    if let Some(guard) = &arm.guard {
+                        self.infer(guard);
+                    }
+
+                    self.block(&arm.body);
+                    self.scopes.pop();
+                }
+
+                None
+            },
+            // Like a `match`, the branches can produce values of different types, so the result is dynamic.
+            Expression::If { condition, then, otherwise, .. } => {
+                self.infer(condition);
+                self.block(then);
+                self.block(otherwise);
+
+                None
+            },
+            Expression::Range { start, end, step, .. } => {
+                for bound in [start, end, step].into_iter().flatten() {
+                    let found = self.infer(bound);
+                    self.expect(&Type::Number, found, TypeErrorType::MismatchedTypes);
+                }
+
+                None
+            },
+        }
+    }
+
+    fn body(&mut self, parameters: &[Parameter], body: &[Node]) {
+        for parameter in parameters {
+            if let Some(r#type) = &parameter.r#type {
+                self.defined(r#type);
+            }
+        }
+
+        let scope = Scope {
+            bindings: parameters.iter().flat_map(|p| match p.name() {
+                Some(name) => vec![(name.to_owned(), Binding::Value(p.r#type.clone()))],
+                // The type of a destructured parameter describes the whole value, so the names it binds are dynamic.
+                None => dynamic(&p.pattern).into_iter().collect(),
+            }).collect(),
+            ..Scope::default()
+        };
+
+        self.scoped(scope, body);
+    }
+
+    /// Infer the type of an expression where a value of the `expected` type is needed. The items in an array literal
+    /// don't need to share a type to fit an array type, so each one is checked against the element type on its own,
+    /// and the array takes the type of the first item that doesn't fit.
+    fn infer_as(&mut self, expected: &Type, expression: &Expression) -> Option<Type> {
+        match (self.resolve(expected), expression) {
+            (Type::Array(element), Expression::Array(items)) => {
+                let types = items.iter().map(|item| self.infer(item)).collect::<Vec<Option<Type>>>();
+                let mismatch = types.into_iter().flatten().find(|found| ! self.fits(&element, found, 0));
+
+                Some(Type::Array(Box::new(mismatch.unwrap_or(*element))))
+            },
+            _ => self.infer(expression),
+        }
+    }
+
+    fn assign(&mut self, target: &Expression, found: Option<Type>) {
+        if let Expression::Identifier(identifier) = target {
+            match self.lookup(identifier) {
+                Some(Binding::Constant(_)) => self.error(TypeErrorType::ConstantAssignment(identifier.clone())),
+                Some(Binding::Value(Some(expected))) => self.expect(&expected, found, TypeErrorType::MismatchedTypes),
+                _ => {},
+            }
+        }
+    }
+
+    /// Record an error if the type that was found is known and can't be used where the expected type is.
+    fn expect(&mut self, expected: &Type, found: Option<Type>, error: impl FnOnce(Type, Type) -> TypeErrorType) {
+        match found {
+            Some(found) if ! self.fits(expected, &found, 0) => self.error(error(expected.clone(), found)),
+            _ => {},
+        }
+    }
+
+    /// Check whether a value of the `found` type can be used where the `expected` type is needed.
+    fn fits(&self, expected: &Type, found: &Type, depth: usize) -> bool {
+        if depth > MAX_ALIAS_DEPTH {
+            return true
+        }
+
+        let fits = |expected: &Type, found: &Type| self.fits(expected, found, depth + 1);
+
+        match (expected, found) {
+            // Undefined aliases have already been reported, so they fit anything.
+            (Type::Named(name), _) => self.alias(name).is_none_or(|expected| fits(&expected, found)),
+            (_, Type::Named(name)) => self.alias(name).is_none_or(|found| fits(expected, &found)),
+            (_, Type::Union(types)) => types.iter().all(|found| fits(expected, found)),
+            (Type::Union(types), _) => types.iter().any(|expected| fits(expected, found)),
+            (Type::Optional(expected), Type::Optional(found)) => fits(expected, found),
+            (Type::Optional(expected), _) => fits(expected, found),
+            (Type::Array(expected), Type::Array(found)) => fits(expected, found),
+            (Type::Map(key, value), Type::Map(found_key, found_value)) => fits(key, found_key) && fits(value, found_value),
+            (Type::Map(key, value), Type::Record(fields)) => fits(key, &Type::String) && fields.iter().all(|(_, found)| fits(value, found)),
+            (Type::Record(expected), Type::Record(found)) => expected.iter().all(|(key, expected)| {
+                found.iter().find(|(k, _)| k == key).is_some_and(|(_, found)| fits(expected, found))
+            }),
+            (Type::Function(parameters, return_type), Type::Function(found_parameters, found_return_type)) => {
+                // Parameters are the other way around: the function that was found has to accept everything the expected one would be given.
+                parameters.len() == found_parameters.len()
+                    && parameters.iter().zip(found_parameters).all(|(expected, found)| fits(found, expected))
+                    && match (return_type, found_return_type) {
+                        (Some(expected), Some(found)) => fits(expected, found),
+                        _ => true,
+                    }
+            },
+            _ => expected == found,
+        }
+    }
+
+    /// Follow a type through any aliases until it's something other than a `Named` type.
+    fn resolve(&self, r#type: &Type) -> Type {
+        let mut r#type = r#type.clone();
+
+        for _ in 0..MAX_ALIAS_DEPTH {
+            match &r#type {
+                Type::Named(name) => match self.alias(name) {
+                    Some(alias) => r#type = alias,
+                    None => break,
+                },
+                _ => break,
+            }
+        }
+
+        r#type
+    }
+
+    /// Report any type names that don't refer to an alias.
+    fn defined(&mut self, r#type: &Type) {
+        match r#type {
+            Type::Named(name) if self.alias(name).is_none() => self.error(TypeErrorType::UndefinedType(name.clone())),
+            Type::Array(item) | Type::Optional(item) => self.defined(item),
+            Type::Map(key, value) => {
+                self.defined(key);
+                self.defined(value);
+            },
+            Type::Record(fields) => fields.iter().for_each(|(_, t)| self.defined(t)),
+            Type::Union(types) => types.iter().for_each(|t| self.defined(t)),
+            Type::Function(parameters, return_type) => {
+                parameters.iter().for_each(|t| self.defined(t));
+
+                if let Some(return_type) = return_type {
+                    self.defined(return_type);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn error(&mut self, err: TypeErrorType) {
+        let (line, span) = self.position;
+
+        self.errors.push(TypeError { line, span, err });
+    }
+
+    fn declare(&mut self, identifier: &str, binding: Binding) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.insert(identifier.to_owned(), binding);
+        }
+    }
+
+    fn lookup(&self, identifier: &str) -> Option<Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.bindings.get(identifier)).cloned()
+    }
+
+    fn alias(&self, name: &str) -> Option<Type> {
+        self.scopes.iter().rev().find_map(|scope| scope.aliases.get(name)).cloned()
+    }
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The type of a function, if all of its parameters are annotated.
+fn signature(parameters: &[Parameter], return_type: Option<Type>) -> Option<Type> {
+    let parameters = parameters.iter().map(|p| p.r#type.clone()).collect::<Option<Vec<Type>>>()?;
+
+    Some(Type::Function(parameters, return_type.map(Box::new)))
+}
+
+/// Bind each of the names in a pattern to a dynamic value.
+fn dynamic(pattern: &Pattern) -> HashMap<String, Binding> {
+    pattern.bindings().into_iter().map(|name| (name.to_owned(), Binding::Value(None))).collect()
+}
+
+/// Parameters for a function type, which are named after their position.
+fn positional(types: Vec<Type>) -> Vec<Parameter> {
+    types.into_iter().enumerate().map(|(i, t)| Parameter::new((i + 1).to_string(), Some(t))).collect()
+}
+
+fn field(fields: Vec<(String, Type)>, key: &str) -> Option<Type> {
+    fields.into_iter().find(|(k, _)| k == key).map(|(_, t)| t)
+}
+
+/// The type of a binary operation, based on JavaScript's rules for the operators Tonic compiles to.
+fn infix(left: Option<Type>, op: &Op, right: Option<Type>) -> Option<Type> {
+    match op {
+        Op::Add => match (left, right) {
+            (Some(Type::Number), Some(Type::Number)) => Some(Type::Number),
+            (Some(Type::BigInt), Some(Type::BigInt)) => Some(Type::BigInt),
+            (Some(Type::String), _) | (_, Some(Type::String)) => Some(Type::String),
+            _ => None,
+        },
+        // Arithmetic on two `BigInt`s stays a `BigInt`, but it can't be mixed with other numbers.
+        Op::Subtract | Op::Multiply | Op::Divide | Op::Mod | Op::Power => match (left, right) {
+            (Some(Type::BigInt), Some(Type::BigInt)) => Some(Type::BigInt),
+            (Some(Type::BigInt), _) | (_, Some(Type::BigInt)) => None,
+            _ => Some(Type::Number),
+        },
+        Op::GreaterThan | Op::LessThan | Op::GreaterThanEquals | Op::LessThanEquals | Op::Equals | Op::NotEquals => Some(Type::Bool),
+        // `??` only gives back the right-hand side when the left-hand side is missing, so an optional value on the left
+        // doesn't make the result optional.
+        Op::Coalesce => match (left, right) {
+            (Some(Type::Optional(left)), Some(right)) if *left == right => Some(right),
+            (Some(left), Some(right)) if left == right => Some(left),
+            _ => None,
+        },
+        // `&&` and `||` return one of their operands, which is only a `bool` if both of them are.
+        Op::And | Op::Or if left == Some(Type::Bool) && right == Some(Type::Bool) => Some(Type::Bool),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic_parser::parse;
+
+    fn check(source: &str) -> Vec<(usize, String)> {
+        Checker::new().check(&parse(source).unwrap())
+            .into_iter()
+            .map(|e| (e.line, e.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn unannotated_code_is_dynamic() {
+        assert_eq!(check(r##"
+            let a = 1
+            a = "one"
+
+            fn add(a, b) {
+                return a + b
+            }
+
+            let b: string = add(1, 2)
+        "##), vec![]);
+    }
+
+    #[test]
+    fn for_loops() {
+        assert_eq!(check(r##"
+            let total: number = 0
+
+            for i in 0.."ten" {
+                total += i
+            }
+
+            let items: [number] = [1, 2, 3]
+            let head: [number] = items[..2]
+            let first: number = items[1..]
+
+            for key, value in {"a": 1} {
+                let name: string = key
+            }
+        "##), vec![
+            (4, "mismatched types, expected `number` but found `string`".to_owned()),
+            (10, "mismatched types, expected `number` but found `[number]`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn matches() {
+        assert_eq!(check(r##"
+            let total: number = 0
+
+            match [1, 2] {
+                [a, b] if a > b => {
+                    total = a
+                }
+                _ => {
+                    let name: string = total
+                }
+            }
+        "##), vec![
+            (9, "mismatched types, expected `string` but found `number`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn destructuring() {
+        assert_eq!(check(r##"
+            let [a, ...rest]: [number] = ["one"]
+            let {"name": name} = {"name": 1}
+            let label: string = name
+
+            fn first([head, ...], {"x": x}: {"x": number}) {
+                return head + x
+            }
+
+            first([1], {"x": "two"})
+
+            for [key, value] in [["a", 1]] {
+                let total: number = key + value
+            }
+        "##), vec![
+            (2, "mismatched types, expected `[number]` but found `[string]`".to_owned()),
+            (10, "argument `2` of `first` expects a `{\"x\": number}` but found `{\"x\": string}`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(check(r##"
+            const limit = 10
+            const [a, b] = [1, 2]
+            let total = 0
+
+            limit = 20
+            a += 1
+            total += limit
+
+            fn shadow(limit) {
+                limit = 1
+            }
+
+            while true {
+                let b = 3
+                b = 4
+            }
+
+            fn reset() {
+                b = 0
+            }
+        "##), vec![
+            (6, "cannot assign to `limit` because it's a `const`".to_owned()),
+            (7, "cannot assign to `a` because it's a `const`".to_owned()),
+            (20, "cannot assign to `b` because it's a `const`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn mismatched_assignments() {
+        assert_eq!(check(r##"
+            let a: number = "one"
+            let b: string = "two"
+            b = 2
+            b += 3
+            let c: number = 1
+            c += "four"
+            let d: bool = 1 < 2 && true
+        "##), vec![
+            (2, "mismatched types, expected `number` but found `string`".to_owned()),
+            (4, "mismatched types, expected `string` but found `number`".to_owned()),
+            (7, "mismatched types, expected `number` but found `string`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn mixed_arrays() {
+        assert_eq!(check(r##"
+            let a: [number] = [1, "a", true]
+            let b: [number | string] = [1, "a"]
+            let c: [number] = [1, 2]
+            fn d(): [string] {
+                return ["a", 1]
+            }
+        "##), vec![
+            (2, "mismatched types, expected `[number]` but found `[string]`".to_owned()),
+            (6, "`d` should return a `[string]` but returns `[number]`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn bigints() {
+        assert_eq!(check(r##"
+            let a: bigint = 10n * 2n - -1n
+            let b: number = 10n
+            let c: bigint = 0xFF
+            let d = 1n + 1
+            let e = 2 ** 3n
+            let f = 1n < 2
+        "##), vec![
+            (3, "mismatched types, expected `number` but found `bigint`".to_owned()),
+            (4, "mismatched types, expected `bigint` but found `number`".to_owned()),
+            (5, "mismatched types, expected `bigint` but found `number`".to_owned()),
+            (6, "mismatched types, expected `number` but found `bigint`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn nulls() {
+        assert_eq!(check(r##"
+            let name: string? = null
+            let a: string = name ?? "anonymous"
+            let b: number = name ?? "anonymous"
+            let c: number = config?.["retries"] ?? 3
+            name ??= 1
+        "##), vec![
+            (4, "mismatched types, expected `number` but found `string`".to_owned()),
+            (6, "mismatched types, expected `string?` but found `number`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn wrong_arguments() {
+        assert_eq!(check(r##"
+            greet("Ryan", 1)
+            greet(1, "Ryan")
+            greet(name(), 1)
+
+            fn greet(name: string, times: number) {}
+            fn name() :: string {
+                return "Ryan"
+            }
+        "##), vec![
+            (3, "argument `name` of `greet` expects a `string` but found `number`".to_owned()),
+            (3, "argument `times` of `greet` expects a `number` but found `string`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn bad_returns() {
+        assert_eq!(check(r##"
+            fn double(n: number) :: number {
+                if n > 10 {
+                    return "too big"
+                }
+
+                let f = fn () {
+                    return "closures aren't checked"
+                }
+
+                return n * 2
+            }
+        "##), vec![
+            (4, "`double` should return a `number` but returns `string`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn rich_types() {
+        assert_eq!(check(r##"
+            type Point = {"x": number, "y": number}
+            type Id = string | number
+
+            let origin: Point = {"x": 0, "y": 0}
+            let incomplete: Point = {"x": 0}
+            let id: Id = 1
+            let ids: [Id] = ["a", "b"]
+            let scores: {string: number} = {"ryan": 1}
+            let name: string? = "Ryan"
+            let required: string = name
+            let x: number = origin.x
+            let missing: Vector = 1
+        "##), vec![
+            (6, r#"mismatched types, expected `Point` but found `{"x": number}`"#.to_owned()),
+            (11, "mismatched types, expected `string` but found `string?`".to_owned()),
+            (13, "cannot find type `Vector`".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn function_types() {
+        assert_eq!(check(r##"
+            fn apply(f: fn(number) -> number, n: number) :: number {
+                return f(n)
+            }
+
+            apply(fn (n: number) -> n * 2, 1)
+            apply(fn (s: string) -> s, 1)
+            apply(double, 1)
+
+            fn double(n: number) :: number {
+                let f: fn(number) -> bool = double
+                f("two")
+
+                return n * 2
+            }
+        "##), vec![
+            (7, "argument `f` of `apply` expects a `fn(number) -> number` but found `fn(string)`".to_owned()),
+            (11, "mismatched types, expected `fn(number) -> bool` but found `fn(number) -> number`".to_owned()),
+            (12, "argument `1` of `f` expects a `number` but found `string`".to_owned()),
+        ]);
+    }
+}
\ No newline at end of file
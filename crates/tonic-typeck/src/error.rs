@@ -0,0 +1,42 @@
+use tonic_parser::{Span, Type};
+use std::fmt::{Display, Formatter, Result};
+
+/// A `TypeError` points at the statement that broke one of the program's type annotations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError {
+    pub line: usize,
+    pub span: Span,
+    pub err: TypeErrorType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeErrorType {
+    /// A value was assigned to a binding that was annotated with another type: `(expected, found)`.
+    MismatchedTypes(Type, Type),
+    /// A function was called with the wrong type of argument: `(function, parameter, expected, found)`.
+    InvalidArgument(String, String, Type, Type),
+    /// A function returned a value of the wrong type: `(function, expected, found)`.
+    InvalidReturn(String, Type, Type),
+    /// A type annotation used a name that isn't a built-in type or an alias.
+    UndefinedType(String),
+    /// A `const` was reassigned, or updated with an operator such as `+=`.
+    ConstantAssignment(String),
+}
+
+impl Display for TypeErrorType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::MismatchedTypes(expected, found) => write!(f, "mismatched types, expected `{}` but found `{}`", expected, found),
+            Self::InvalidArgument(function, parameter, expected, found) => write!(f, "argument `{}` of `{}` expects a `{}` but found `{}`", parameter, function, expected, found),
+            Self::InvalidReturn(function, expected, found) => write!(f, "`{}` should return a `{}` but returns `{}`", function, expected, found),
+            Self::UndefinedType(name) => write!(f, "cannot find type `{}`", name),
+            Self::ConstantAssignment(name) => write!(f, "cannot assign to `{}` because it's a `const`", name),
+        }
+    }
+}
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.err)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+use tonic_parser::Program;
+
+mod checker;
+mod error;
+
+pub use checker::Checker;
+pub use error::{TypeError, TypeErrorType};
+
+/// Check that a Tonic program agrees with its own type annotations.
+///
+/// Only annotated code is checked. Bindings and parameters without an annotation are dynamically typed, so a
+/// program without any annotations always passes.
+pub fn check(program: &Program) -> Result<(), Vec<TypeError>> {
+    let errors = Checker::new().check(program);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
\ No newline at end of file
@@ -1,5 +1,8 @@
-use tonic_compiler::compile;
-use rquickjs::{BuiltinLoader, BuiltinResolver, FileResolver, Runtime, ModuleLoader, ScriptLoader, Context, Func, Value, Rest, bind, qjs::JSValue};
+// The `#[bind]` macro from `rquickjs` generates `impl` blocks inside of functions.
+#![allow(non_local_definitions)]
+
+use tonic_compiler::{compile_with_options, Diagnostic, Options, SourceMap};
+use rquickjs::{BuiltinLoader, BuiltinResolver, FileResolver, Runtime, ModuleLoader, ScriptLoader, Context, Ctx, Func, Function, Value, Rest, Coerced, bind};
 use rustyline::{Editor, error::ReadlineError};
 use structopt::StructOpt;
 
@@ -13,6 +16,12 @@ struct Cli {
     #[structopt(long = "raw", short = "r", help = "Execute the specified file as raw JavaScript")]
     raw: bool,
 
+    #[structopt(long = "emit", short = "e", help = "Write the compiled JavaScript and its source map next to the file instead of running it")]
+    emit: bool,
+
+    #[structopt(long = "checked", short = "c", help = "Check annotated parameters and return values at runtime")]
+    checked: bool,
+
     #[structopt(long = "version", short = "v", help = "Output the current version of Tonic.")]
     version: bool,
 
@@ -23,19 +32,36 @@ const POLYFILL: &str = include_str!("../js/polyfill.js");
 const WEB_MODULE: &str = include_str!("../dist/web.js");
 const JSON_MODULE: &str = include_str!("../js/json.js");
 
-pub fn println(vs: Rest<Value>) {
-    fn stringify(v: Value) -> String {
+pub fn println<'js>(ctx: Ctx<'js>, vs: Rest<Value<'js>>) {
+    fn stringify<'js>(ctx: Ctx<'js>, v: Value<'js>) -> String {
         match true {
             _ if v.is_string() => v.into_string().unwrap().to_string().unwrap(),
             _ if v.is_number() => v.as_number().unwrap().to_string(),
             _ if v.is_bool() => v.as_bool().unwrap().to_string(),
-            _ if v.is_array() => v.into_array().unwrap().into_iter().map(|v| stringify(v.unwrap())).collect::<Vec<String>>().join(", "),
-            _ => unimplemented!(),
+            _ if v.is_array() => v.into_array().unwrap().into_iter().map(|v| stringify(ctx, v.unwrap())).collect::<Vec<String>>().join(", "),
+            _ => {
+                // `rquickjs` doesn't know about every type of value, such as a `BigInt`, so JavaScript's `typeof` is asked instead.
+                let type_of: Function = ctx.eval("(value) => value === null ? \"null\" : typeof value").unwrap();
+                let string: Function = ctx.globals().get("String").unwrap();
+
+                match type_of.call::<_, String>((v.clone(),)).unwrap().as_str() {
+                    "bigint" => v.get::<Coerced<String>>().unwrap().0,
+                    kind @ ("null" | "undefined") => kind.to_owned(),
+                    // Objects are printed as JSON, unless they can't be, such as when they hold a `BigInt`.
+                    "object" => {
+                        let json: Function = ctx.eval("(value) => JSON.stringify(value)").unwrap();
+
+                        json.call::<_, String>((v.clone(),)).unwrap_or_else(|_| string.call((v,)).unwrap())
+                    },
+                    // Anything else, such as a function, is printed with its own `toString()`.
+                    _ => string.call((v,)).unwrap(),
+                }
+            },
         }
     }
 
     for v in vs.into_inner().into_iter() {
-        println!("{}", stringify(v));
+        println!("{}", stringify(ctx, v));
     }
 }
 
@@ -43,6 +69,7 @@ pub fn println(vs: Rest<Value>) {
 #[quickjs(bare)]
 mod token {
     use tonic_compiler::{lex, TokenKind};
+    use rquickjs::{Error, Result};
 
     #[derive(Clone)]
     #[quickjs(cloneable)]
@@ -57,7 +84,7 @@ mod token {
             }
         }
 
-        pub fn all(&self) -> Vec<(String, String)> {
+        pub fn all(&self) -> Result<Vec<(String, String)>> {
             let tokens = lex(&self.source[..]);
             let mut js = Vec::new();
 
@@ -66,6 +93,9 @@ mod token {
                     TokenKind::Identifier(s) => (s, "Identifier".to_owned()),
                     TokenKind::String(s) => (s, "String".to_owned()),
                     TokenKind::Number(n) => (n.to_string(), "Number".to_owned()),
+                    TokenKind::BigInt(n) => (n, "BigInt".to_owned()),
+                    TokenKind::DocComment(s) => (s, "DocComment".to_owned()),
+                    TokenKind::Error(e) => (e.to_string(), "Error".to_owned()),
                     TokenKind::Fn => ("fn".to_owned(), "Fn".to_owned()),
                     TokenKind::LeftParen => ("(".to_string(), "LeftParen".to_owned()),
                     TokenKind::RightParen => (")".to_string(), "RightParen".to_owned()),
@@ -73,14 +103,15 @@ mod token {
                     TokenKind::RightBracket => ("]".to_string(), "RightBracket".to_owned()),
                     TokenKind::LeftBrace => ("{".to_string(), "LeftBrace".to_owned()),
                     TokenKind::RightBrace => ("}".to_string(), "RightBrace".to_owned()),
-                    _ => unimplemented!("{:?}", token.kind)
+                    // Scripts find out about tokens that can't be given to them yet with an exception, rather than a crash.
+                    kind => return Err(Error::new_into_js_message("token", "array", format!("the `{:?}` token isn't supported yet", kind))),
                 });
             }
 
-            js
+            Ok(js)
         }
 
-        pub fn tokenize(source: String) -> Vec<(String, String)> {
+        pub fn tokenize(source: String) -> Result<Vec<(String, String)>> {
             let this = Self::new(source);
 
             this.all()
@@ -168,6 +199,8 @@ mod uuid {
             }
         }
 
+        // This is exposed to scripts as `toString()`, so it can't be replaced with `Display`.
+        #[allow(clippy::inherent_to_string)]
         pub fn to_string(&self) -> String {
             self.value.clone()
         }
@@ -260,10 +293,25 @@ fn main() {
     
     if let Some(file) = args.file {
         let contents = read(file.clone());
-        let compiled = [
-            POLYFILL.to_string(),
-            if args.raw { contents } else { compile(&contents[..]) }
-        ].join("\n");
+        let (code, map) = if args.raw {
+            (contents.clone(), None)
+        } else {
+            let mut options = Options::new();
+            options.checked(args.checked);
+
+            match compile_with_options(&contents[..], &options) {
+                Ok((compiled, map)) => (compiled, Some(map)),
+                Err(diagnostics) => report(&file, &contents, diagnostics),
+            }
+        };
+
+        if args.emit {
+            emit(&file, &code, map.as_ref());
+
+            std::process::exit(0);
+        }
+
+        let compiled = [POLYFILL.to_string(), code].join("\n");
 
         let fqp = std::fs::canonicalize(file.clone()).unwrap();
         let fqd = fqp.parent().unwrap();
@@ -284,7 +332,9 @@ fn main() {
                 println!("=== EVAL ===");
             }
             
-            ctx.compile(file, compiled).unwrap();
+            if let Err(error) = ctx.compile(file.clone(), compiled) {
+                uncaught(&file, &contents, map.as_ref(), error);
+            }
         });
     
         if args.debug {
@@ -326,4 +376,70 @@ fn main() {
 
 fn read(path: String) -> String {
     std::fs::read_to_string(path).unwrap()
+}
+
+/// Write the compiled JavaScript to `<file>.js`, along with its source map in `<file>.js.map`.
+fn emit(file: &str, code: &str, map: Option<&SourceMap>) {
+    let output = format!("{}.js", file.trim_end_matches(".tn"));
+    let name = |path: &str| std::path::Path::new(path).file_name().unwrap().to_string_lossy().to_string();
+
+    match map {
+        Some(map) => {
+            std::fs::write(&output, format!("{}//# sourceMappingURL={}.map\n", code, name(&output))).unwrap();
+            std::fs::write(format!("{}.map", output), map.to_json(&name(&output), &name(file))).unwrap();
+        },
+        None => std::fs::write(&output, code).unwrap(),
+    }
+}
+
+/// Report an exception that was thrown while running a script, pointing the stack trace at the Tonic source
+/// instead of the generated JavaScript.
+fn uncaught(file: &str, source: &str, map: Option<&SourceMap>, error: rquickjs::Error) -> ! {
+    let (message, stack) = match error {
+        rquickjs::Error::Exception { message, stack, .. } => (message, stack),
+        error => (error.to_string(), String::new()),
+    };
+
+    let frames = stack.lines().map(|frame| remap(frame, file, map)).collect::<Vec<(String, Option<(usize, usize)>)>>();
+    let message = format!("uncaught exception: {}", message);
+
+    match frames.iter().find_map(|(_, position)| *position) {
+        Some((line, column)) => eprint!("{}", Diagnostic::new(message, line, (column, column + 1)).render(file, source)),
+        None => eprintln!("error: {}", message),
+    }
+
+    for (frame, _) in frames {
+        eprintln!("{}", frame);
+    }
+
+    std::process::exit(1);
+}
+
+/// QuickJS stack frames look like `at main (file.tn:42)`, where the line is counted from the start of the polyfill.
+/// Frames that point into the compiled script are rewritten to the line and column in the Tonic source.
+fn remap(frame: &str, file: &str, map: Option<&SourceMap>) -> (String, Option<(usize, usize)>) {
+    let prefix = format!("({}:", file);
+
+    let position = map.zip(frame.find(&prefix)).and_then(|(map, start)| {
+        let start = start + prefix.len();
+        let end = start + frame[start..].find(')')?;
+        let line = frame[start..end].parse::<usize>().ok()?.checked_sub(POLYFILL.matches('\n').count() + 1)?;
+
+        map.lookup(line.checked_sub(1)?, 0).map(|(line, column)| (start, end, line + 1, column + 1))
+    });
+
+    match position {
+        Some((start, end, line, column)) => (format!("{}{}:{}{}", &frame[..start], line, column, &frame[end..]), Some((line, column))),
+        None => (frame.to_owned(), None),
+    }
+}
+
+fn report(file: &str, source: &str, diagnostics: Vec<Diagnostic>) -> ! {
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic.render(file, source));
+    }
+
+    eprintln!("error: aborting due to {} previous error{}", diagnostics.len(), if diagnostics.len() == 1 { "" } else { "s" });
+
+    std::process::exit(1);
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+//! In checked mode, values that don't match their annotations should throw a `TypeError` when the program runs.
+
+mod common;
+
+use common::evaluate_checked;
+
+#[test]
+fn valid_values_pass_through() {
+    let source = r#"
+type Point = {"x": number, "y": number}
+
+fn describe(point: Point, tags: [string], name: string?): string {
+    return "ok"
+}
+
+describe({"x": 1, "y": 2}, ["a", "b"], "point") + describe({"x": 1, "y": 2}, [], undefined)
+"#;
+
+    assert_eq!(evaluate_checked(source), Ok("okok".to_owned()));
+}
+
+#[test]
+fn invalid_values_throw() {
+    // Unannotated values are dynamic, so the static checker lets them through to the runtime assertions.
+    let arguments = "fn add(a: number, b: number): number {\n    return a + b\n}\n\nfn id(x) {\n    return x\n}\n\nadd(id(\"1\"), 2)";
+    let returns = "fn name(x): string {\n    return x\n}\n\nname(1)";
+    let nested = "fn total(items: [number]): number {\n    return 0\n}\n\nfn id(x) {\n    return x\n}\n\ntotal(id([1, [2]]))";
+
+    let error = |source| evaluate_checked(source).unwrap_err();
+
+    assert!(error(arguments).contains("parameter `a` of `add` expected `number` but found string on line 1"), "{}", error(arguments));
+    assert!(error(returns).contains("return value of `name` expected `string` but found number on line 2"), "{}", error(returns));
+    assert!(error(nested).contains("parameter `items` of `total` expected `[number]` but found array on line 1"), "{}", error(nested));
+}
\ No newline at end of file
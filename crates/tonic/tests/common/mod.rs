@@ -0,0 +1,54 @@
+//! Helpers shared by the integration tests, which compile Tonic and run the output with QuickJS.
+
+// Each test file only uses some of the helpers.
+#![allow(dead_code)]
+
+use rquickjs::{Context, FromJs, Runtime};
+use std::process::{Command, Output};
+use tonic_compiler::{compile, compile_with_options, Options};
+
+/// Evaluate some JavaScript in a fresh context, giving back the value of its last expression or the error it threw.
+pub fn evaluate<T: for<'js> FromJs<'js>>(js: &str) -> Result<T, String> {
+    let runtime = Runtime::new().unwrap();
+    let context = Context::full(&runtime).unwrap();
+
+    context.with(|ctx| ctx.eval::<T, _>(js).map_err(|e| e.to_string()))
+}
+
+/// Compile a program with runtime type assertions turned on.
+pub fn compile_checked(source: &str) -> String {
+    let mut options = Options::new();
+    options.checked(true);
+
+    compile_with_options(source, &options).unwrap().0
+}
+
+/// Run a program in checked mode, giving back the value of its last expression or the error that it threw.
+pub fn evaluate_checked(source: &str) -> Result<String, String> {
+    evaluate(&compile_checked(source))
+}
+
+/// Run a program that appends whatever it wants to check to `output`, returning the final value of `output`.
+pub fn run(source: &str) -> String {
+    collect(&compile(source).unwrap())
+}
+
+/// The same as `run()`, but with runtime type assertions turned on.
+pub fn run_checked(source: &str) -> String {
+    collect(&compile_checked(source))
+}
+
+/// Run a program with the CLI, the same as `tonic-cli <file>` would. `name` keeps the file apart from any other test's.
+pub fn cli(name: &str, source: &str) -> Output {
+    let file = std::env::temp_dir().join(format!("tonic-{}-{}.tn", name, std::process::id()));
+    std::fs::write(&file, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tonic-cli")).arg(&file).output().unwrap();
+    std::fs::remove_file(&file).unwrap();
+
+    output
+}
+
+fn collect(js: &str) -> String {
+    evaluate(&format!("let output = \"\"; {} output", js)).unwrap()
+}
\ No newline at end of file
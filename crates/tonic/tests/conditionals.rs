@@ -0,0 +1,53 @@
+//! An `if` used as an expression should produce the value of whichever branch runs, whether it compiles to a
+//! conditional expression or to a function that runs statements first.
+
+mod common;
+
+use common::run;
+
+#[test]
+fn values() {
+    assert_eq!(run("for n in [3, 5, 4] {\n    output += if n > 4 { \"big\" } else if n == 4 { \"four\" } else { \"small\" } + \" \"\n}"), "small big four ");
+    assert_eq!(run("let a = 2\noutput += (if a > 1 { 10 } else { 20 }) * 2"), "20");
+}
+
+#[test]
+fn statements() {
+    let source = r#"
+fn describe(items) {
+    let summary = if items.length == 0 {
+        "empty"
+    } else {
+        let total = 0
+        for item in items {
+            total += item
+        }
+        "total " + total
+    }
+
+    return summary
+}
+
+output += describe([]) + ", " + describe([1, 2, 3])
+"#;
+
+    assert_eq!(run(source), "empty, total 6");
+}
+
+#[test]
+fn nested() {
+    let source = r#"
+let grade = fn (score) -> match score {
+    s if s >= 50 => if s >= 80 { "merit" } else {
+        output += "!"
+        "pass"
+    },
+    _ => "fail",
+}
+
+let grades = [grade(90), grade(60), grade(10)]
+output += grades
+"#;
+
+    assert_eq!(run(source), "!merit,pass,fail");
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+//! Destructuring should bind each name to the matching part of the value, wherever a pattern can be written.
+
+mod common;
+
+use common::{run_checked, evaluate_checked};
+
+#[test]
+fn lets() {
+    assert_eq!(run_checked("let [a, _, ...rest] = [1, 2, 3, 4]\noutput += a + \"|\" + rest"), "1|3,4");
+    assert_eq!(run_checked("let [a, b] = [1]\noutput += a + \"|\" + b"), "1|undefined");
+    assert_eq!(run_checked("let {\"name\": n, \"tags\": [first, ...]} = {\"name\": \"tonic\", \"tags\": [\"x\", \"y\"]}\noutput += n + first"), "tonicx");
+}
+
+#[test]
+fn parameters() {
+    assert_eq!(run_checked("let swap = fn ([a, b]) -> [b, a]\noutput += swap([1, 2])"), "2,1");
+    assert_eq!(run_checked("fn area({\"w\": w, \"h\": h}: {\"w\": number, \"h\": number}) {\n    return w * h\n}\noutput += area({\"w\": 2, \"h\": 3})"), "6");
+    assert_eq!(run_checked("let third = fn (_, _, c) -> c\noutput += third(1, 2, 3)"), "3");
+}
+
+#[test]
+fn checked_parameters() {
+    let error = evaluate_checked("fn first([a, ...]: [number]) {\n    return a\n}\nlet id = fn (x) -> x\nfirst(id(\"one\"))").unwrap_err();
+
+    assert!(error.contains("parameter 1 of `first` expected `[number]` but found string"), "{}", error);
+}
+
+#[test]
+fn loops() {
+    assert_eq!(run_checked("for [a, b] in [[1, 2], [3, 4]] {\n    output += a * b\n}"), "212");
+    assert_eq!(run_checked("for key, {\"n\": n} in {\"a\": {\"n\": 1}} {\n    output += key + n\n}"), "a1");
+}
\ No newline at end of file
@@ -0,0 +1,50 @@
+//! `for` loops should visit every value, entry and number in order, and stop early on `break` and `continue`.
+
+mod common;
+
+use common::run;
+
+#[test]
+fn arrays_maps_and_ranges() {
+    assert_eq!(run("for item in [\"a\", \"b\", \"c\"] {\n    output += item\n}"), "abc");
+    assert_eq!(run("for key, value in {\"a\": 1} {\n    output += key + value\n}"), "a1");
+    assert_eq!(run("for i, n in [10, 20] {\n    output += i + n\n}"), "1021");
+    assert_eq!(run("let n = 3\nfor i in 0..n {\n    n = 10\n    output += i\n}"), "012");
+    assert_eq!(run("for i in 2..2 {\n    output += i\n}"), "");
+    assert_eq!(run("for x in [1, 2] {\n    x = x * 10\n    output += x\n}"), "1020");
+    assert_eq!(run("for [a, b] in [[1, 2]] {\n    a = b\n    output += a\n}"), "2");
+}
+
+#[test]
+fn ranges() {
+    assert_eq!(run("for i in 1..=3 {\n    output += i\n}"), "123");
+    assert_eq!(run("for i in 10..0 step -4 {\n    output += i\n}"), "1062");
+    assert_eq!(run("let step = 0 - 1\nfor i in 3..=1 step step {\n    output += i\n}"), "321");
+    assert_eq!(run("let step = 3\nfor i in 0..step {\n    output += i\n}"), "012");
+    assert_eq!(run("let r = 0..\noutput += 1\nfor i in r {\n    if i > 2 {\n        break\n    }\n    output += i\n}"), "1012");
+    assert_eq!(run("let r = 0..3\nfor i in r {\n    output += i\n}\nfor i in r {\n    output += i\n}"), "012012");
+    assert_eq!(run("for i in 0.. step 5 {\n    if i > 10 {\n        break\n    }\n    output += i\n}"), "0510");
+    assert_eq!(run("let items = [1, 2, 3, 4]\noutput += items[1..3] + \"|\" + items[..=1] + \"|\" + \"hello\"[..2]"), "2,3|1,2|he");
+    assert_eq!(run("let items = [1, 2, 3]\nfor k in [0 - 1, 0, 1] {\n    output += items[..=k] + \"|\"\n}"), "1,2,3|1|1,2|");
+}
+
+#[test]
+fn breaking_and_continuing() {
+    let source = r#"
+'outer: for i in 0..5 {
+    for j in 0..5 {
+        if j > i {
+            continue 'outer
+        }
+
+        if i == 3 {
+            break 'outer
+        }
+
+        output += j
+    }
+}
+"#;
+
+    assert_eq!(run(source), "001012");
+}
\ No newline at end of file
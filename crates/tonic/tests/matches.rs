@@ -0,0 +1,136 @@
+//! `match` should pick the first arm whose pattern matches and whose guard passes, whether it's used as a statement
+//! or produces a value.
+
+mod common;
+
+use common::run;
+
+const DESCRIBE: &str = r#"
+fn describe(value) {
+    return match value {
+        1 | -1 => "one",
+        "x" | true => "literal",
+        [a, b] if a > b => "descending",
+        [_, [c]] => "nested " + c,
+        [_, _] => "pair",
+        {"k": v, "n": 2} => "map " + v,
+        _ => "other",
+    }
+}
+"#;
+
+#[test]
+fn values() {
+    let cases = [
+        ("1", "one"),
+        ("-1", "one"),
+        ("\"x\"", "literal"),
+        ("true", "literal"),
+        ("[2, 1]", "descending"),
+        ("[1, [5]]", "nested 5"),
+        ("[1, 2]", "pair"),
+        ("[1, 2, 3]", "other"),
+        ("{\"k\": \"v\", \"n\": 2}", "map v"),
+        ("{\"k\": \"v\"}", "other"),
+        ("false", "other"),
+    ];
+
+    for (value, expected) in cases {
+        assert_eq!(run(&format!("{}\noutput = describe({})", DESCRIBE, value)), expected, "{}", value);
+    }
+}
+
+#[test]
+fn statements() {
+    let source = r#"
+for i in 0..6 {
+    match i {
+        0 => {
+            continue
+        }
+        n if n % 2 == 0 => {
+            output += "even"
+        }
+        5 => {
+            break
+        }
+        _ => {
+            output += i
+        }
+    }
+}
+"#;
+
+    assert_eq!(run(source), "1even3even");
+}
+
+#[test]
+fn guarded_bindings() {
+    let source = r#"
+for i in [-2, 0, 3] {
+    match i {
+        n if n > 0 => {
+            output += "pos" + n
+        }
+        n if n < 0 => {
+            output += "neg" + n
+        }
+        _ => {
+            output += "zero"
+        }
+    }
+}
+"#;
+
+    assert_eq!(run(source), "neg-2zeropos3");
+}
+
+#[test]
+fn guarded_bindings_in_values() {
+    let source = r#"
+for v in [-2, 0, 3] {
+    output += match v {
+        n if n > 0 => "pos",
+        n if n < 0 => "neg",
+        _ => "zero",
+    }
+}
+"#;
+
+    assert_eq!(run(source), "negzeropos");
+}
+
+#[test]
+fn rest() {
+    let source = r#"
+for items in [[], [1], [1, 2, 3]] {
+    output += match items {
+        [first, ...rest] => first + ":" + rest.length + " ",
+        _ => "empty ",
+    }
+}
+"#;
+
+    assert_eq!(run(source), "empty 1:0 1:2 ");
+}
+
+#[test]
+fn shadowing() {
+    let source = r#"
+let y = "outer"
+match "first" {
+    y => {
+        let z = y
+        output += z
+    }
+}
+let z = " then "
+output += z + y
+match [y] {
+    [y] => output += " " + y,
+    _ => {},
+}
+"#;
+
+    assert_eq!(run(source), "first then outer outer");
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+//! Optional chaining and `??` should let decoded JSON be traversed without checking every level by hand.
+
+mod common;
+
+use common::run;
+
+#[test]
+fn optional_chaining() {
+    let source = r#"
+let users = [{"name": "Ryan", "address": {"city": "Leeds"}}, {"name": "Guest"}, null]
+for user in users {
+    output += (user?.address?.city ?? "unknown") + ","
+    output += (user?.["name"] ?? "nobody") + ";"
+}
+"#;
+
+    assert_eq!(run(source), "Leeds,Ryan;unknown,Guest;unknown,nobody;");
+}
+
+#[test]
+fn nullish_coalescing() {
+    assert_eq!(run("let a = 0\nlet b = null\nlet c = \"\"\noutput += [a ?? 1, b ?? 2, c ?? 3, a || 4]"), "0,2,,4");
+    assert_eq!(run("let options = {}\noptions.retries ??= 3\noptions.retries ??= 5\noutput += options.retries"), "3");
+    assert_eq!(run("let map = {\"k\": 1}\noutput += [map.missing == null, map.k != null, null == null]"), "true,true,true");
+}
+
+#[test]
+fn null_patterns() {
+    let source = r#"
+for value in [null, 0, {}.missing] {
+    output += match value {
+        null => "none",
+        _ => "some",
+    } + " "
+}
+"#;
+
+    assert_eq!(run(source), "none some none ");
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+//! Numeric literals in every base should have the same value in JavaScript, and `BigInt`s should stay exact.
+
+mod common;
+
+use common::run;
+
+#[test]
+fn bases_separators_and_exponents() {
+    assert_eq!(run("output += [0xFF, 0o17, 0b1010, 1_000_000, 2.5e3, 1e-3]"), "255,15,10,1000000,2500,0.001");
+}
+
+#[test]
+fn bigints() {
+    assert_eq!(run("output += 9_007_199_254_740_993n * 1_000n + 0xFFn"), "9007199254740993255");
+    assert_eq!(run("let n = 2n\nfor i in 0..100 {\n    n *= 2n\n}\noutput += n"), "2535301200456458802993406410752");
+    assert_eq!(run("output += match 7n / 2n {\n    3n => \"truncated\",\n    _ => \"rounded\",\n}"), "truncated");
+}
\ No newline at end of file
@@ -0,0 +1,172 @@
+//! Round-trip tests for operator precedence: random expression trees are written out as fully parenthesised Tonic,
+//! compiled to JavaScript and evaluated by QuickJS, then compared against evaluating the same tree directly.
+//!
+//! The compiler is free to drop any parentheses it likes, so a wrong answer means the printer dropped one too many.
+
+use rquickjs::{Context, Runtime};
+use tonic_compiler::compile;
+
+const CASES: usize = 500;
+const DEPTH: usize = 6;
+
+/// A small xorshift generator, seeded so that failures can be reproduced.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+#[derive(Debug)]
+enum Tree {
+    Number(f64),
+    Bool(bool),
+    Negate(Box<Tree>),
+    Not(Box<Tree>),
+    Infix(Box<Tree>, &'static str, Box<Tree>),
+    /// `if condition { then } else { otherwise }`, which compiles to a conditional expression.
+    If(Box<Tree>, Box<Tree>, Box<Tree>),
+}
+
+const ARITHMETIC: &[&str] = &["+", "-", "*", "/", "%", "**"];
+const COMPARISON: &[&str] = &["<", ">", "<=", ">=", "==", "!="];
+const LOGICAL: &[&str] = &["&&", "||", "??"];
+
+fn number(rng: &mut Rng, depth: usize) -> Tree {
+    if depth == 0 || rng.below(4) == 0 {
+        return Tree::Number(if rng.below(5) == 0 { rng.below(20) as f64 / 4.0 } else { rng.below(10) as f64 });
+    }
+
+    if rng.below(6) == 0 {
+        return Tree::Negate(Box::new(number(rng, depth - 1)));
+    }
+
+    if rng.below(8) == 0 {
+        return Tree::If(Box::new(bool(rng, depth - 1)), Box::new(number(rng, depth - 1)), Box::new(number(rng, depth - 1)));
+    }
+
+    let op = ARITHMETIC[rng.below(ARITHMETIC.len() as u64) as usize];
+
+    Tree::Infix(Box::new(number(rng, depth - 1)), op, Box::new(number(rng, depth - 1)))
+}
+
+fn bool(rng: &mut Rng, depth: usize) -> Tree {
+    if depth == 0 || rng.below(6) == 0 {
+        return Tree::Bool(rng.below(2) == 0);
+    }
+
+    match rng.below(4) {
+        0 => Tree::Not(Box::new(bool(rng, depth - 1))),
+        1 => {
+            let op = LOGICAL[rng.below(LOGICAL.len() as u64) as usize];
+
+            Tree::Infix(Box::new(bool(rng, depth - 1)), op, Box::new(bool(rng, depth - 1)))
+        },
+        _ => {
+            let op = COMPARISON[rng.below(COMPARISON.len() as u64) as usize];
+
+            Tree::Infix(Box::new(number(rng, depth - 1)), op, Box::new(number(rng, depth - 1)))
+        },
+    }
+}
+
+fn tonic(tree: &Tree) -> String {
+    match tree {
+        Tree::Number(n) => n.to_string(),
+        Tree::Bool(b) => b.to_string(),
+        Tree::Negate(tree) => format!("(-{})", tonic(tree)),
+        Tree::Not(tree) => format!("(!{})", tonic(tree)),
+        Tree::Infix(left, op, right) => format!("({} {} {})", tonic(left), op, tonic(right)),
+        Tree::If(condition, then, otherwise) => format!("(if {} {{ {} }} else {{ {} }})", tonic(condition), tonic(then), tonic(otherwise)),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    Number(f64),
+    Bool(bool),
+}
+
+fn evaluate(tree: &Tree) -> Value {
+    let number = |tree: &Tree| match evaluate(tree) {
+        Value::Number(n) => n,
+        Value::Bool(_) => unreachable!(),
+    };
+
+    let bool = |tree: &Tree| match evaluate(tree) {
+        Value::Bool(b) => b,
+        Value::Number(_) => unreachable!(),
+    };
+
+    match tree {
+        Tree::Number(n) => Value::Number(*n),
+        Tree::Bool(b) => Value::Bool(*b),
+        Tree::Negate(tree) => Value::Number(-number(tree)),
+        Tree::Not(tree) => Value::Bool(!bool(tree)),
+        Tree::If(condition, then, otherwise) => if bool(condition) { evaluate(then) } else { evaluate(otherwise) },
+        Tree::Infix(left, op, right) => match *op {
+            "+" => Value::Number(number(left) + number(right)),
+            "-" => Value::Number(number(left) - number(right)),
+            "*" => Value::Number(number(left) * number(right)),
+            "/" => Value::Number(number(left) / number(right)),
+            "%" => Value::Number(number(left) % number(right)),
+            "**" => Value::Number(power(number(left), number(right))),
+            "<" => Value::Bool(number(left) < number(right)),
+            ">" => Value::Bool(number(left) > number(right)),
+            "<=" => Value::Bool(number(left) <= number(right)),
+            ">=" => Value::Bool(number(left) >= number(right)),
+            "==" => Value::Bool(number(left) == number(right)),
+            "!=" => Value::Bool(number(left) != number(right)),
+            "&&" => Value::Bool(bool(left) && bool(right)),
+            "||" => Value::Bool(bool(left) || bool(right)),
+            // Neither side can be `null`, but JavaScript still needs parentheses to mix `??` with `&&` and `||`.
+            "??" => Value::Bool(bool(left)),
+            _ => unreachable!(),
+        },
+    }
+}
+
+/// JavaScript's `**` differs from C's `pow` when the exponent is `NaN`, or when `±1` is raised to an infinite power.
+fn power(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        f64::NAN
+    } else {
+        base.powf(exponent)
+    }
+}
+
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+        _ => a == b,
+    }
+}
+
+#[test]
+fn precedence_round_trips() {
+    let runtime = Runtime::new().unwrap();
+    let context = Context::full(&runtime).unwrap();
+    let mut rng = Rng(0x5eed_1234_abcd_ef01);
+
+    for case in 0..CASES {
+        let tree = if case % 2 == 0 { number(&mut rng, DEPTH) } else { bool(&mut rng, DEPTH) };
+        let source = tonic(&tree);
+        let js = compile(&source).unwrap();
+        let expected = evaluate(&tree);
+
+        let actual = context.with(|ctx| match expected {
+            Value::Number(_) => Value::Number(ctx.eval::<f64, _>(js.as_str()).unwrap()),
+            Value::Bool(_) => Value::Bool(ctx.eval::<bool, _>(js.as_str()).unwrap()),
+        });
+
+        assert!(same(&expected, &actual), "case {}: `{}` compiled to `{}` gave {:?}, expected {:?}", case, source, js.trim(), actual, expected);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,29 @@
+//! `println` should print whatever it's given when a program is run with the CLI, one value per line.
+
+mod common;
+
+/// Run a program with the CLI, giving back what it printed.
+fn run(name: &str, source: &str) -> String {
+    let output = common::cli(name, source);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(run("primitives", "println(\"a\", 1.5, true, [1, 2])"), "a\n1.5\ntrue\n1, 2\n");
+}
+
+#[test]
+fn bigints() {
+    assert_eq!(run("bigints", "println(10n, -12345678901234567890n, [1n, 2])"), "10\n-12345678901234567890\n1, 2\n");
+}
+
+#[test]
+fn nulls_objects_and_functions() {
+    let source = "let f = fn (x) -> x\nprintln(null, undefined, {\"b\": [true, null]}, {\"n\": 1n}, f, [null, 2n])";
+
+    assert_eq!(run("others", source), "null\nundefined\n{\"b\":[true,null]}\n[object Object]\n(x) => {\n    return x;\n}\nnull, 2\n");
+}
\ No newline at end of file
@@ -0,0 +1,68 @@
+//! Strings with characters that need escaping should come out of QuickJS exactly as they went into the compiler.
+
+mod common;
+
+use common::evaluate;
+use tonic_compiler::compile;
+
+#[test]
+fn strings_round_trip() {
+    let strings = [
+        "plain",
+        "quote \" and backslash \\",
+        "new\nline, carriage\rreturn and\ttab",
+        "null \0 then 1",
+        "line \u{2028} and paragraph \u{2029} separators",
+        "escape \u{1b} and delete \u{7f}",
+        "unicode é ✓ 🎉",
+        "\"; throw new Error(\"injected\"); \"",
+    ];
+
+    for string in strings {
+        let source = format!("{{\"{}\": \"{}\"}}", escape(string), escape(string));
+        let js = compile(&source).unwrap();
+
+        let entry: Vec<String> = evaluate(&format!("(() => {{ const object = {} return Object.entries(object)[0]; }})()", js)).unwrap();
+
+        assert_eq!(entry, vec![string, string], "`{}`", js.trim());
+    }
+}
+
+#[test]
+fn interpolated_strings_round_trip() {
+    let source = r#"
+let name = "`$\{world\}`"
+let items = {"count": 2}
+"Hello, {name}! \{{items["count"] + 1}\} {"nested: {name}"} \"quoted\"\n"
+"#;
+
+    let js = compile(source).unwrap();
+    let output: String = evaluate(&js).unwrap();
+
+    assert_eq!(output, "Hello, `${world}`! {3} nested: `${world}` \"quoted\"\n");
+}
+
+/// Escape the characters that the Tonic lexer needs escaping, leaving everything else for the compiler to deal with.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\0', "\\0").replace('{', "\\{").replace('}', "\\}")
+}
+
+#[test]
+fn raw_and_multiline_strings_round_trip() {
+    let source = r####"
+let table = 'users'
+let pattern = r#"^\d+ "{x}"$"#
+let query = """
+    SELECT *
+      FROM {table}
+    WHERE name = '\u{e9}\x21'
+    """
+let strings = [pattern, query]
+strings
+"####;
+
+    let js = compile(source).unwrap();
+    let output: Vec<String> = evaluate(&js).unwrap();
+
+    assert_eq!(output, vec![r#"^\d+ "{x}"$"#, "SELECT *\n  FROM users\nWHERE name = 'é!'"]);
+}
\ No newline at end of file
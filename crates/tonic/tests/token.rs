@@ -0,0 +1,21 @@
+//! `@std/token` should give scripts the tokens in a string of Tonic, and throw for the ones it can't describe yet.
+
+mod common;
+
+use common::cli;
+
+#[test]
+fn tokens() {
+    let output = cli("token", "use Lexer from \"@std/token\"\n\nprintln(Lexer.tokenize(\"f(a)\"))");
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "f, Identifier, (, LeftParen, a, Identifier, ), RightParen\n");
+}
+
+#[test]
+fn unsupported_tokens() {
+    let output = cli("token-unsupported", "use Lexer from \"@std/token\"\n\nprintln(Lexer.tokenize(\"let a = 1\"))");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(! output.status.success());
+    assert!(stderr.contains("the `Let` token isn't supported yet"), "{}", stderr);
+}
\ No newline at end of file